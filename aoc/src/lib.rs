@@ -0,0 +1,242 @@
+use std::error::Error;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use common::ParseMode;
+
+pub const DAYS: u32 = 25;
+
+pub const USAGE: &str = "Usage:
+    aoc run <day> [--part <1|2>] [--input <path>] [--strict]
+    aoc run --all [--input-dir <dir>] [--strict]";
+
+pub enum Command {
+    Run {
+        day: u32,
+        parts: Vec<u32>,
+        input: PathBuf,
+        mode: ParseMode,
+    },
+    RunAll {
+        input_dir: PathBuf,
+        mode: ParseMode,
+    },
+}
+
+#[derive(Debug)]
+pub struct ArgsError(String);
+
+#[derive(Debug)]
+pub struct UnknownDayError(u32);
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ArgsError {}
+
+impl fmt::Display for UnknownDayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no puzzle for day {}", self.0)
+    }
+}
+
+impl Error for UnknownDayError {}
+
+pub fn input_path(dir: &Path, day: u32) -> PathBuf {
+    dir.join(format!("day{day:02}")).join("input.txt")
+}
+
+fn parse_number(value: Option<String>, name: &str, max: u32) -> Result<u32, ArgsError> {
+    let value = value.ok_or_else(|| ArgsError(format!("missing value for {name}")))?;
+
+    match value.parse() {
+        Ok(number) if (1..=max).contains(&number) => Ok(number),
+        _ => Err(ArgsError(format!(
+            "invalid {name} `{value}`, expected a number from 1 to {max}"
+        ))),
+    }
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, ArgsError> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("run") => {}
+        Some(other) => return Err(ArgsError(format!("unknown command `{other}`"))),
+        None => return Err(ArgsError(String::from("missing command"))),
+    }
+
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut input_dir = None;
+    let mut all = false;
+    let mut mode = ParseMode::Lenient;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--strict" => mode = ParseMode::Strict,
+            "--part" => part = Some(parse_number(args.next(), "part", 2)?),
+            "--input" => {
+                input =
+                    Some(PathBuf::from(args.next().ok_or_else(|| {
+                        ArgsError(String::from("missing value for input"))
+                    })?))
+            }
+            "--input-dir" => {
+                input_dir =
+                    Some(PathBuf::from(args.next().ok_or_else(|| {
+                        ArgsError(String::from("missing value for input-dir"))
+                    })?))
+            }
+            a if a.starts_with("--") => {
+                return Err(ArgsError(format!("unknown option `{a}`")));
+            }
+            _ if day.is_none() => day = Some(parse_number(Some(arg), "day", DAYS)?),
+            _ => return Err(ArgsError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    if all {
+        if day.is_some() || part.is_some() || input.is_some() {
+            return Err(ArgsError(String::from(
+                "--all cannot be combined with a day, --part or --input",
+            )));
+        }
+
+        return Ok(Command::RunAll {
+            input_dir: input_dir.unwrap_or_else(|| PathBuf::from(".")),
+            mode,
+        });
+    }
+
+    let day = day.ok_or_else(|| ArgsError(String::from("missing day")))?;
+    let input =
+        input.unwrap_or_else(|| input_path(&input_dir.unwrap_or_else(|| PathBuf::from(".")), day));
+
+    Ok(Command::Run {
+        day,
+        parts: part.map_or_else(|| vec![1, 2], |p| vec![p]),
+        input,
+        mode,
+    })
+}
+
+pub fn solve(
+    day: u32,
+    part: u32,
+    input: impl Read,
+    mode: ParseMode,
+) -> Result<String, Box<dyn Error>> {
+    match day {
+        1 => common::solve::<day01::Document>(input, part, mode),
+        2 => common::solve::<day02::Record>(input, part, mode),
+        3 => common::solve::<day03::Schematic>(input, part, mode),
+        4 => common::solve::<day04::Pile>(input, part, mode),
+        5 => common::solve::<day05::Almanac>(input, part, mode),
+        6 => common::solve::<day06::RaceSheet>(input, part, mode),
+        7 => common::solve::<day07::CamelCards>(input, part, mode),
+        8 => common::solve::<day08::Map>(input, part, mode),
+        9 => common::solve::<day09::Report>(input, part, mode),
+        10 => common::solve::<day10::Map>(input, part, mode),
+        11 => common::solve::<day11::Image>(input, part, mode),
+        12 => common::solve::<day12::Records>(input, part, mode),
+        13 => common::solve::<day13::Notes>(input, part, mode),
+        14 => common::solve::<day14::Platform>(input, part, mode),
+        15 => common::solve::<day15::InitializationSequence>(input, part, mode),
+        16 => common::solve::<day16::Layout>(input, part, mode),
+        17 => common::solve::<day17::Map>(input, part, mode),
+        18 => common::solve::<day18::DigPlan>(input, part, mode),
+        19 => common::solve::<day19::Processor>(input, part, mode),
+        20 => common::solve::<day20::Network>(input, part, mode),
+        21 => common::solve::<day21::Map>(input, part, mode),
+        22 => common::solve::<day22::Snapshot>(input, part, mode),
+        23 => common::solve::<day23::Map>(input, part, mode),
+        24 => common::solve::<day24::Hailstorm>(input, part, mode),
+        25 => common::solve::<day25::Apparatus>(input, part, mode),
+        _ => Err(Box::new(UnknownDayError(day))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_ascii_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_run_single_part() -> Result<(), Box<dyn Error>> {
+        match parse_args(args("run 5 --part 2 --input path/to/input.txt"))? {
+            Command::Run {
+                day,
+                parts,
+                input,
+                mode,
+            } => {
+                assert_eq!(5, day);
+                assert_eq!(vec![2], parts);
+                assert_eq!(PathBuf::from("path/to/input.txt"), input);
+                assert_eq!(ParseMode::Lenient, mode);
+            }
+            Command::RunAll { .. } => panic!("expected a single day"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_run_default_input() -> Result<(), Box<dyn Error>> {
+        match parse_args(args("run 17"))? {
+            Command::Run {
+                day, parts, input, ..
+            } => {
+                assert_eq!(17, day);
+                assert_eq!(vec![1, 2], parts);
+                assert_eq!(Path::new(".").join("day17").join("input.txt"), input);
+            }
+            Command::RunAll { .. } => panic!("expected a single day"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_run_all() -> Result<(), Box<dyn Error>> {
+        match parse_args(args("run --all --input-dir inputs --strict"))? {
+            Command::RunAll { input_dir, mode } => {
+                assert_eq!(PathBuf::from("inputs"), input_dir);
+                assert_eq!(ParseMode::Strict, mode);
+            }
+            Command::Run { .. } => panic!("expected all days"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_args() {
+        assert!(parse_args(args("run 26")).is_err());
+        assert!(parse_args(args("run 5 --part 3")).is_err());
+        assert!(parse_args(args("run 5 --all")).is_err());
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("walk 5")).is_err());
+    }
+
+    #[test]
+    fn solve_dispatches_to_day() -> Result<(), Box<dyn Error>> {
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+
+        assert_eq!("288", solve(6, 1, input.as_bytes(), ParseMode::Lenient)?);
+        assert_eq!("71503", solve(6, 2, input.as_bytes(), ParseMode::Strict)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+use aoc::*;
+use common::ParseMode;
+
+fn run_part(day: u32, part: u32, input: &Path, mode: ParseMode) -> Result<String, Box<dyn Error>> {
+    let file = File::open(input).map_err(|e| format!("{}: {e}", input.display()))?;
+
+    solve(day, part, file, mode)
+}
+
+fn print_table(rows: &[[String; 4]]) {
+    let mut widths = [0; 4];
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let cells = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>();
+
+        println!("| {} |", cells.join(" | "));
+
+        if index == 0 {
+            let separators = widths
+                .iter()
+                .map(|&width| "-".repeat(width))
+                .collect::<Vec<_>>();
+
+            println!("|-{}-|", separators.join("-|-"));
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Run {
+            day,
+            parts,
+            input,
+            mode,
+        } => {
+            for part in parts {
+                println!(
+                    "Day {day} part {part}: {}",
+                    run_part(day, part, &input, mode)?
+                );
+            }
+        }
+        Command::RunAll { input_dir, mode } => {
+            let mut rows = vec![[
+                String::from("Day"),
+                String::from("Part 1"),
+                String::from("Part 2"),
+                String::from("Time"),
+            ]];
+
+            for day in 1..=DAYS {
+                let input = input_path(&input_dir, day);
+                let start = Instant::now();
+
+                let [part1, part2] = [1, 2].map(|part| {
+                    run_part(day, part, &input, mode).unwrap_or_else(|e| format!("error: {e}"))
+                });
+
+                let elapsed = start.elapsed();
+
+                rows.push([
+                    day.to_string(),
+                    part1,
+                    part2,
+                    format!("{:.1} ms", elapsed.as_secs_f64() * 1000.0),
+                ]);
+            }
+
+            print_table(&rows);
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(command) {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::Read;
+
+mod parse;
+
+pub use parse::{
+    next_token, parse_glyph, parse_glyphs, parse_number, parse_number_with, FromGlyph, ParseError,
+    ParseErrorKind, ParseMode,
+};
+
+pub trait Solution: Sized {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>>;
+
+    fn parse(input: impl Read) -> Result<Self, Box<dyn Error>> {
+        Self::parse_with(input, ParseMode::Lenient)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>>;
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>>;
+}
+
+#[derive(Debug)]
+pub struct UnsolvedError;
+
+#[derive(Debug)]
+pub struct InvalidPartError(pub u32);
+
+impl fmt::Display for UnsolvedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no solution for this part")
+    }
+}
+
+impl Error for UnsolvedError {}
+
+impl fmt::Display for InvalidPartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid part {}", self.0)
+    }
+}
+
+impl Error for InvalidPartError {}
+
+pub fn solve<S: Solution>(
+    input: impl Read,
+    part: u32,
+    mode: ParseMode,
+) -> Result<String, Box<dyn Error>> {
+    let puzzle = S::parse_with(input, mode)?;
+
+    match part {
+        1 => Ok(puzzle.part1()?.to_string()),
+        2 => Ok(puzzle.part2()?.to_string()),
+        _ => Err(Box::new(InvalidPartError(part))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, BufRead};
+
+    struct Lines {
+        lines: Vec<String>,
+    }
+
+    impl Solution for Lines {
+        fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+            let lines = io::BufReader::new(input)
+                .lines()
+                .collect::<Result<_, _>>()?;
+
+            Ok(Self { lines })
+        }
+
+        fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+            Ok(self.lines.len())
+        }
+
+        fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+            Err::<usize, _>(Box::new(UnsolvedError))
+        }
+    }
+
+    #[test]
+    fn solve_parts() -> Result<(), Box<dyn Error>> {
+        let mode = ParseMode::Lenient;
+
+        assert_eq!("3", solve::<Lines>("a\nb\nc".as_bytes(), 1, mode)?);
+        assert!(solve::<Lines>("a\nb\nc".as_bytes(), 2, mode).is_err());
+        assert!(solve::<Lines>("a\nb\nc".as_bytes(), 3, mode).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    #[default]
+    Lenient,
+    Strict,
+}
+
+pub trait FromGlyph: Sized {
+    fn from_glyph(glyph: char) -> Option<Self>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Missing(&'static str),
+    Invalid(&'static str),
+    InvalidNumber,
+    WrongCount {
+        what: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    text: String,
+    offset: usize,
+    len: usize,
+    line: Option<usize>,
+}
+
+fn offset_of(text: &str, span: &str) -> usize {
+    let start = text.as_ptr() as usize;
+    let span_start = span.as_ptr() as usize;
+
+    if span_start >= start && span_start + span.len() <= start + text.len() {
+        span_start - start
+    } else {
+        0
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::Missing(what) => write!(f, "missing {what}"),
+            ParseErrorKind::Invalid(what) => write!(f, "invalid {what}"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::WrongCount {
+                what,
+                expected,
+                found,
+            } => write!(f, "expected {expected} {what}, found {found}"),
+        }
+    }
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, text: &str, span: &str) -> Self {
+        Self {
+            kind,
+            text: text.to_string(),
+            offset: offset_of(text, span),
+            len: span.len(),
+            line: None,
+        }
+    }
+
+    pub fn missing(what: &'static str, text: &str) -> Self {
+        Self::new(ParseErrorKind::Missing(what), text, &text[text.len()..])
+    }
+
+    pub fn invalid(what: &'static str, text: &str, span: &str) -> Self {
+        Self::new(ParseErrorKind::Invalid(what), text, span)
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn within(mut self, text: &str, span: &str) -> Self {
+        self.offset += offset_of(text, span);
+        self.text = text.to_string();
+        self
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn token(&self) -> &str {
+        self.text
+            .get(self.offset..self.offset + self.len)
+            .unwrap_or_default()
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.text
+            .get(..self.offset)
+            .map_or(0, |prefix| prefix.chars().count())
+            + 1
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+
+        if !self.token().is_empty() {
+            write!(f, " `{}`", self.token())?;
+        }
+
+        if let Some(line) = self.line {
+            write!(f, " at line {line}, column {}", self.column())?;
+        } else {
+            write!(f, " at column {}", self.column())?;
+        }
+
+        let carets = "^".repeat(self.token().chars().count().max(1));
+
+        write!(
+            f,
+            "\n  {}\n  {}{carets}",
+            self.text,
+            " ".repeat(self.column() - 1)
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+pub fn next_token<'a>(
+    parts: &mut impl Iterator<Item = &'a str>,
+    what: &'static str,
+    text: &str,
+) -> Result<&'a str, ParseError> {
+    parts.next().ok_or_else(|| ParseError::missing(what, text))
+}
+
+pub fn parse_number<T: FromStr>(token: &str, text: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, text, token))
+}
+
+pub fn parse_number_with<T: FromStr + Default>(
+    token: &str,
+    text: &str,
+    mode: ParseMode,
+) -> Result<T, ParseError> {
+    match mode {
+        ParseMode::Lenient => Ok(token.parse().unwrap_or_default()),
+        ParseMode::Strict => parse_number(token, text),
+    }
+}
+
+pub fn parse_glyph<T: FromGlyph + From<char>>(
+    glyph: &str,
+    text: &str,
+    mode: ParseMode,
+) -> Result<T, ParseError> {
+    let c = glyph.chars().next().unwrap_or_default();
+
+    match (T::from_glyph(c), mode) {
+        (Some(value), _) => Ok(value),
+        (None, ParseMode::Lenient) => Ok(c.into()),
+        (None, ParseMode::Strict) => Err(ParseError::invalid("character", text, glyph)),
+    }
+}
+
+pub fn parse_glyphs<T: FromGlyph + From<char>>(
+    text: &str,
+    mode: ParseMode,
+) -> Result<Vec<T>, ParseError> {
+    text.char_indices()
+        .map(|(index, c)| parse_glyph(&text[index..index + c.len_utf8()], text, mode))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_caret_under_token() {
+        let line = "Game 3: 8 green, x6 blue";
+        let token = &line[17..19];
+        let error = parse_number::<u32>(token, line).unwrap_err().at_line(3);
+
+        assert_eq!(Some(3), error.line());
+        assert_eq!(18, error.column());
+        assert_eq!("x6", error.token());
+        assert_eq!(
+            "invalid number `x6` at line 3, column 18
+  Game 3: 8 green, x6 blue
+                   ^^",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn reanchor_within_line() {
+        let line = "Card 1: 41 48 | 83 8x";
+        let numbers = &line[16..];
+        let error = parse_number::<u32>(&numbers[3..], numbers)
+            .unwrap_err()
+            .within(line, numbers);
+
+        assert_eq!(20, error.column());
+        assert_eq!(line, error.text());
+    }
+
+    #[test]
+    fn render_missing_at_end() {
+        let error = ParseError::missing("bid", "32T3K");
+
+        assert_eq!(6, error.column());
+        assert_eq!(
+            "missing bid at column 6
+  32T3K
+       ^",
+            error.to_string()
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Tile {
+        Empty,
+        Wall,
+    }
+
+    impl FromGlyph for Tile {
+        fn from_glyph(glyph: char) -> Option<Self> {
+            match glyph {
+                '.' => Some(Tile::Empty),
+                '#' => Some(Tile::Wall),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<char> for Tile {
+        fn from(value: char) -> Self {
+            Tile::from_glyph(value).unwrap_or(Tile::Empty)
+        }
+    }
+
+    #[test]
+    fn parse_glyphs_by_mode() {
+        assert_eq!(
+            Ok(vec![Tile::Wall, Tile::Empty, Tile::Empty]),
+            parse_glyphs("#.x", ParseMode::Lenient)
+        );
+
+        let error = parse_glyphs::<Tile>("#.x", ParseMode::Strict).unwrap_err();
+
+        assert_eq!("x", error.token());
+        assert_eq!(3, error.column());
+    }
+
+    #[test]
+    fn parse_number_by_mode() {
+        assert_eq!(
+            Ok(0),
+            parse_number_with::<u32>("x", "x", ParseMode::Lenient)
+        );
+        assert!(parse_number_with::<u32>("x", "x", ParseMode::Strict).is_err());
+    }
+}
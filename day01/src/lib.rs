@@ -1,48 +1,60 @@
 use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 
-const DIGIT_STRINGS: [&str; 10] = [
-    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
-
-pub fn calibrate(input: impl Read, include_strings: bool) -> Result<u32, Box<dyn Error>> {
-    let mut sum = 0;
-
-    for line in io::BufReader::new(input).lines() {
-        let mut first_digit = None;
-        let mut last_digit = 0;
-
-        let line = line?;
-
-        for mut i in 0..line.len() {
-            let mut digit = None;
-
-            let first_char = line[i..].chars().next().unwrap_or_default();
-
-            if first_char.is_digit(10) {
-                digit = Some(first_char.to_digit(10).unwrap_or_default());
-            } else if include_strings {
-                for (index, &string) in DIGIT_STRINGS.iter().enumerate() {
-                    if line[i..].starts_with(string) {
-                        digit = Some(index as u32);
-                        i += string.len() - 1;
-                        break;
-                    }
-                }
-            }
-
-            if let Some(d) = digit {
-                if first_digit.is_none() {
-                    first_digit = Some(d);
-                }
-                last_digit = d;
-            }
-        }
-
-        sum += first_digit.unwrap_or(0) * 10 + last_digit;
+use common::{ParseMode, Solution};
+
+mod matcher;
+mod report;
+
+pub use matcher::{DigitMatch, DigitMatcher, MatchKind, ENGLISH, NUMERALS};
+pub use report::{render, LineReport, ReportFormat, UnknownFormatError};
+
+pub struct Document {
+    lines: Vec<String>,
+}
+
+impl Document {
+    fn calibrate(&self, matcher: &DigitMatcher) -> u32 {
+        self.lines
+            .iter()
+            .map(|line| matcher.calibrate_line(line))
+            .sum()
+    }
+
+    fn report(&self, matcher: &DigitMatcher) -> Vec<LineReport> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| LineReport::new(index + 1, line, matcher))
+            .collect()
+    }
+}
+
+impl Solution for Document {
+    fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let lines = io::BufReader::new(input)
+            .lines()
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { lines })
     }
 
-    Ok(sum)
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.calibrate(&DigitMatcher::numerals()))
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.calibrate(&DigitMatcher::english()))
+    }
+}
+
+pub fn calibrate(input: impl Read, matcher: &DigitMatcher) -> Result<u32, Box<dyn Error>> {
+    Ok(Document::parse(input)?.calibrate(matcher))
+}
+
+pub fn report(input: impl Read, matcher: &DigitMatcher) -> Result<Vec<LineReport>, Box<dyn Error>> {
+    Ok(Document::parse(input)?.report(matcher))
 }
 
 #[cfg(test)]
@@ -56,7 +68,7 @@ pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet";
 
-        assert_eq!(142, calibrate(input.as_bytes(), false)?);
+        assert_eq!(142, calibrate(input.as_bytes(), &DigitMatcher::numerals())?);
 
         Ok(())
     }
@@ -71,7 +83,23 @@ xtwone3four
 zoneight234
 7pqrstsixteen";
 
-        assert_eq!(281, calibrate(input.as_bytes(), true)?);
+        assert_eq!(281, calibrate(input.as_bytes(), &DigitMatcher::english())?);
+
+        let reports = report(input.as_bytes(), &DigitMatcher::english())?;
+
+        assert_eq!(
+            281,
+            reports.iter().map(LineReport::calibration).sum::<u32>()
+        );
+        assert_eq!(
+            Some(DigitMatch {
+                start: 7,
+                end: 12,
+                value: 3,
+                kind: MatchKind::Word,
+            }),
+            reports[1].last
+        );
 
         Ok(())
     }
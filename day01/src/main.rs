@@ -1,17 +1,49 @@
+use std::env;
 use std::error::Error;
 use std::fs::File;
 
 use day01::*;
 
+const USAGE: &str = "Usage:
+    day01
+    day01 report [--numerals] [--format <text|csv|json>] [--input <path>]";
+
+fn print_report(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut matcher = DigitMatcher::english();
+    let mut format = ReportFormat::Text;
+    let mut input = String::from("input.txt");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--numerals" => matcher = DigitMatcher::numerals(),
+            "--format" => format = args.next().ok_or("missing value for format")?.parse()?,
+            "--input" => input = args.next().ok_or("missing value for input")?,
+            _ => return Err(format!("unexpected argument `{arg}`\n\n{USAGE}").into()),
+        }
+    }
+
+    print!("{}", render(&report(File::open(input)?, &matcher)?, format));
+
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("report") => return print_report(args),
+        Some(other) => return Err(format!("unknown command `{other}`\n\n{USAGE}").into()),
+        None => {}
+    }
+
     println!(
         "Calibration without strings: {}",
-        calibrate(File::open("input.txt")?, false)?
+        calibrate(File::open("input.txt")?, &DigitMatcher::numerals())?
     );
 
     println!(
         "Calibration with strings: {}",
-        calibrate(File::open("input.txt")?, true)?
+        calibrate(File::open("input.txt")?, &DigitMatcher::english())?
     );
 
     Ok(())
@@ -0,0 +1,272 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+pub const NUMERALS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+pub const ENGLISH: [(&str, u32); 10] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchKind {
+    Numeral,
+    Word,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DigitMatch {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+    pub kind: MatchKind,
+}
+
+#[derive(Clone, Copy)]
+struct Output {
+    len: usize,
+    value: u32,
+    kind: MatchKind,
+}
+
+#[derive(Clone, Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    fail: usize,
+    outputs: Vec<Output>,
+}
+
+#[derive(Clone)]
+pub struct DigitMatcher {
+    nodes: Vec<Node>,
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchKind::Numeral => write!(f, "numeral"),
+            MatchKind::Word => write!(f, "word"),
+        }
+    }
+}
+
+impl Default for DigitMatcher {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl DigitMatcher {
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        let mut nodes = vec![Node::default()];
+
+        for (word, value) in words {
+            if word.is_empty() {
+                continue;
+            }
+
+            let mut node = 0;
+
+            for c in word.chars() {
+                node = match nodes[node].next.get(&c) {
+                    Some(&next) => next,
+                    None => {
+                        let next = nodes.len();
+
+                        nodes.push(Node::default());
+                        nodes[node].next.insert(c, next);
+                        next
+                    }
+                };
+            }
+
+            let kind = if word.chars().all(|c| c.is_ascii_digit()) {
+                MatchKind::Numeral
+            } else {
+                MatchKind::Word
+            };
+
+            nodes[node].outputs.push(Output {
+                len: word.len(),
+                value,
+                kind,
+            });
+        }
+
+        let mut matcher = Self { nodes };
+        let mut queue = matcher.nodes[0]
+            .next
+            .values()
+            .copied()
+            .collect::<VecDeque<_>>();
+
+        while let Some(node) = queue.pop_front() {
+            let children = matcher.nodes[node]
+                .next
+                .iter()
+                .map(|(&c, &child)| (c, child))
+                .collect::<Vec<_>>();
+
+            for (c, child) in children {
+                let fail = matcher.step(matcher.nodes[node].fail, c);
+                let inherited = matcher.nodes[fail].outputs.clone();
+
+                matcher.nodes[child].fail = fail;
+                matcher.nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        matcher
+    }
+
+    pub fn numerals() -> Self {
+        Self::new(NUMERALS)
+    }
+
+    pub fn english() -> Self {
+        Self::new(NUMERALS.into_iter().chain(ENGLISH))
+    }
+
+    fn step(&self, mut node: usize, c: char) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].next.get(&c) {
+                return next;
+            }
+
+            if node == 0 {
+                return 0;
+            }
+
+            node = self.nodes[node].fail;
+        }
+    }
+
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = DigitMatch> + 'a {
+        line.char_indices()
+            .scan(0, move |node, (index, c)| {
+                *node = self.step(*node, c);
+
+                Some((index + c.len_utf8(), *node))
+            })
+            .flat_map(move |(end, node)| {
+                self.nodes[node]
+                    .outputs
+                    .iter()
+                    .map(move |output| DigitMatch {
+                        start: end - output.len,
+                        end,
+                        value: output.value,
+                        kind: output.kind,
+                    })
+            })
+    }
+
+    pub fn first_and_last(&self, line: &str) -> Option<(DigitMatch, DigitMatch)> {
+        let mut first: Option<DigitMatch> = None;
+        let mut last: Option<DigitMatch> = None;
+
+        for found in self.matches(line) {
+            if first.is_none_or(|first| first.start > found.start) {
+                first = Some(found);
+            }
+
+            if last.is_none_or(|last| last.start < found.start) {
+                last = Some(found);
+            }
+        }
+
+        Some((first?, last?))
+    }
+
+    pub fn calibrate_line(&self, line: &str) -> u32 {
+        self.first_and_last(line)
+            .map_or(0, |(first, last)| first.value * 10 + last.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts_and_values(matcher: &DigitMatcher, line: &str) -> Vec<(usize, u32)> {
+        matcher
+            .matches(line)
+            .map(|found| (found.start, found.value))
+            .collect()
+    }
+
+    #[test]
+    fn match_overlapping_words() {
+        let matcher = DigitMatcher::english();
+
+        assert_eq!(
+            vec![(0, 8), (4, 2), (7, 3)],
+            starts_and_values(&matcher, "eightwothree")
+        );
+        assert_eq!(
+            Some((
+                DigitMatch {
+                    start: 1,
+                    end: 4,
+                    value: 1,
+                    kind: MatchKind::Word,
+                },
+                DigitMatch {
+                    start: 4,
+                    end: 5,
+                    value: 7,
+                    kind: MatchKind::Numeral,
+                }
+            )),
+            matcher.first_and_last("zone7")
+        );
+        assert_eq!(None, DigitMatcher::numerals().first_and_last("oneight"));
+    }
+
+    #[test]
+    fn match_custom_vocabulary() {
+        let german = [
+            ("eins", 1),
+            ("zwei", 2),
+            ("drei", 3),
+            ("vier", 4),
+            ("fünf", 5),
+            ("sechs", 6),
+            ("sieben", 7),
+            ("acht", 8),
+            ("neun", 9),
+        ];
+        let matcher = DigitMatcher::new(NUMERALS.into_iter().chain(german));
+
+        assert_eq!(51, matcher.calibrate_line("xfünfzweins"));
+        assert_eq!(83, matcher.calibrate_line("achtsechsiebendrei"));
+        assert_eq!(0, matcher.calibrate_line("eight"));
+
+        let matcher = DigitMatcher::new([("ten", 1), ("tent", 2), ("entente", 3)]);
+
+        assert_eq!(
+            vec![(0, 1), (0, 2), (3, 1), (3, 2), (1, 3), (6, 1), (6, 2)],
+            starts_and_values(&matcher, "tententent")
+        );
+    }
+}
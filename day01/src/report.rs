@@ -0,0 +1,245 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::matcher::{DigitMatch, DigitMatcher};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineReport {
+    pub line: usize,
+    pub text: String,
+    pub first: Option<DigitMatch>,
+    pub last: Option<DigitMatch>,
+}
+
+#[derive(Debug)]
+pub struct UnknownFormatError(String);
+
+impl fmt::Display for UnknownFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown report format `{}`, expected text, csv or json",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownFormatError {}
+
+impl FromStr for ReportFormat {
+    type Err = UnknownFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ReportFormat::Text),
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(UnknownFormatError(s.to_string())),
+        }
+    }
+}
+
+impl LineReport {
+    pub fn new(line: usize, text: &str, matcher: &DigitMatcher) -> Self {
+        let (first, last) = matcher.first_and_last(text).unzip();
+
+        Self {
+            line,
+            text: text.to_string(),
+            first,
+            last,
+        }
+    }
+
+    pub fn calibration(&self) -> u32 {
+        self.first.map_or(0, |first| first.value) * 10 + self.last.map_or(0, |last| last.value)
+    }
+}
+
+impl fmt::Display for LineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} -> {}",
+            self.line,
+            self.text,
+            self.calibration()
+        )?;
+
+        match (self.first, self.last) {
+            (Some(first), Some(last)) => write!(
+                f,
+                " (first {} {}..{} {}, last {} {}..{} {})",
+                first.value,
+                first.start,
+                first.end,
+                first.kind,
+                last.value,
+                last.start,
+                last.end,
+                last.kind
+            ),
+            _ => write!(f, " (no digits)"),
+        }
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+fn json_match(found: Option<DigitMatch>) -> String {
+    found.map_or_else(
+        || String::from("null"),
+        |found| {
+            format!(
+                "{{\"digit\": {}, \"start\": {}, \"end\": {}, \"kind\": \"{}\"}}",
+                found.value, found.start, found.end, found.kind
+            )
+        },
+    )
+}
+
+fn csv_match(found: Option<DigitMatch>) -> String {
+    found.map_or_else(
+        || String::from(",,,"),
+        |found| {
+            format!(
+                "{},{},{},{}",
+                found.value, found.start, found.end, found.kind
+            )
+        },
+    )
+}
+
+pub fn render(reports: &[LineReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Text => reports.iter().map(|report| format!("{report}\n")).collect(),
+        ReportFormat::Csv => {
+            let mut csv = String::from(
+                "line,text,first_digit,first_start,first_end,first_kind,\
+                 last_digit,last_start,last_end,last_kind,calibration\n",
+            );
+
+            for report in reports {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{}",
+                    report.line,
+                    csv_field(&report.text),
+                    csv_match(report.first),
+                    csv_match(report.last),
+                    report.calibration()
+                );
+            }
+
+            csv
+        }
+        ReportFormat::Json => {
+            let lines = reports
+                .iter()
+                .map(|report| {
+                    format!(
+                        "  {{\"line\": {}, \"text\": {}, \"first\": {}, \"last\": {}, \"calibration\": {}}}",
+                        report.line,
+                        json_string(&report.text),
+                        json_match(report.first),
+                        json_match(report.last),
+                        report.calibration()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            if lines.is_empty() {
+                String::from("[]\n")
+            } else {
+                format!("[\n{}\n]\n", lines.join(",\n"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reports() -> Vec<LineReport> {
+        let matcher = DigitMatcher::english();
+
+        ["two1nine", "a,\"b\"", "7pqrstsixteen"]
+            .iter()
+            .enumerate()
+            .map(|(index, line)| LineReport::new(index + 1, line, &matcher))
+            .collect()
+    }
+
+    #[test]
+    fn render_text() {
+        assert_eq!(
+            "line 1: two1nine -> 29 (first 2 0..3 word, last 9 4..8 word)
+line 2: a,\"b\" -> 0 (no digits)
+line 3: 7pqrstsixteen -> 76 (first 7 0..1 numeral, last 6 6..9 word)
+",
+            render(&reports(), ReportFormat::Text)
+        );
+    }
+
+    #[test]
+    fn render_csv() {
+        assert_eq!(
+            "line,text,first_digit,first_start,first_end,first_kind,last_digit,last_start,last_end,last_kind,calibration
+1,two1nine,2,0,3,word,9,4,8,word,29
+2,\"a,\"\"b\"\"\",,,,,,,,,0
+3,7pqrstsixteen,7,0,1,numeral,6,6,9,word,76
+",
+            render(&reports(), ReportFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        assert_eq!(
+            r#"[
+  {"line": 1, "text": "two1nine", "first": {"digit": 2, "start": 0, "end": 3, "kind": "word"}, "last": {"digit": 9, "start": 4, "end": 8, "kind": "word"}, "calibration": 29},
+  {"line": 2, "text": "a,\"b\"", "first": null, "last": null, "calibration": 0},
+  {"line": 3, "text": "7pqrstsixteen", "first": {"digit": 7, "start": 0, "end": 1, "kind": "numeral"}, "last": {"digit": 6, "start": 6, "end": 9, "kind": "word"}, "calibration": 76}
+]
+"#,
+            render(&reports(), ReportFormat::Json)
+        );
+        assert_eq!("[]\n", render(&[], ReportFormat::Json));
+    }
+}
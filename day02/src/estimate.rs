@@ -0,0 +1,303 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::{Cubes, Game, Record};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BagEstimate {
+    pub most_likely: Cubes,
+    pub intervals: Vec<(String, RangeInclusive<u32>)>,
+}
+
+const MAX_BAGS: u128 = 1 << 20;
+
+#[derive(Debug)]
+pub enum EstimateError {
+    InvalidConfidence(f64),
+    TooManyBags { game: u32, bags: u128 },
+}
+
+impl fmt::Display for EstimateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EstimateError::InvalidConfidence(confidence) => write!(
+                f,
+                "invalid confidence {confidence}, expected a value between 0 and 1"
+            ),
+            EstimateError::TooManyBags { game, bags } => write!(
+                f,
+                "game {game} has {bags} candidate bags, at most {MAX_BAGS} can be searched"
+            ),
+        }
+    }
+}
+
+impl Error for EstimateError {}
+
+fn ln_factorial(n: u64) -> f64 {
+    if n < 16 {
+        return (2..=n).map(|i| (i as f64).ln()).sum();
+    }
+
+    let n = n as f64;
+
+    n * n.ln() - n + 0.5 * (2.0 * std::f64::consts::PI * n).ln() + 1.0 / (12.0 * n)
+        - 1.0 / (360.0 * n.powi(3))
+}
+
+fn ln_choose(n: u64, k: u64) -> f64 {
+    ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k)
+}
+
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    1.0 - polynomial * (-x * x).exp()
+}
+
+fn likelihood_drop(confidence: f64) -> f64 {
+    let (mut low, mut high) = (0.0, 10.0);
+
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+
+        if erf(middle) < confidence {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    high * high
+}
+
+impl Game {
+    fn estimate_bag(
+        &self,
+        max_per_color: u32,
+        confidence: f64,
+    ) -> Result<BagEstimate, EstimateError> {
+        let minimal = self.minimal_bag();
+        let colors = minimal.iter().collect::<Vec<_>>();
+        let ranges = colors
+            .iter()
+            .map(|&(_, min)| min..=min.max(max_per_color))
+            .collect::<Vec<_>>();
+
+        let bags = ranges
+            .iter()
+            .map(|range| u128::from(range.end() - range.start()) + 1)
+            .fold(1u128, u128::saturating_mul);
+
+        if bags > MAX_BAGS {
+            return Err(EstimateError::TooManyBags {
+                game: self.id,
+                bags,
+            });
+        }
+
+        let draws = self
+            .sets
+            .iter()
+            .map(|set| {
+                let counts = colors
+                    .iter()
+                    .map(|&(color, _)| set.count(color))
+                    .collect::<Vec<_>>();
+                let drawn = counts.iter().map(|&count| u64::from(count)).sum::<u64>();
+
+                (counts, drawn)
+            })
+            .collect::<Vec<_>>();
+
+        let mut bag = ranges
+            .iter()
+            .map(|range| *range.start())
+            .collect::<Vec<_>>();
+        let mut profiles = ranges
+            .iter()
+            .map(|range| vec![f64::NEG_INFINITY; (range.end() - range.start() + 1) as usize])
+            .collect::<Vec<_>>();
+        let (mut best, mut best_likelihood) = (bag.clone(), f64::NEG_INFINITY);
+
+        loop {
+            let total = bag.iter().map(|&count| u64::from(count)).sum::<u64>();
+            let likelihood = draws
+                .iter()
+                .map(|(counts, drawn)| {
+                    counts
+                        .iter()
+                        .zip(&bag)
+                        .map(|(&k, &n)| ln_choose(n.into(), k.into()))
+                        .sum::<f64>()
+                        - ln_choose(total, *drawn)
+                })
+                .sum::<f64>();
+
+            if likelihood > best_likelihood {
+                (best, best_likelihood) = (bag.clone(), likelihood);
+            }
+
+            for ((profile, range), &n) in profiles.iter_mut().zip(&ranges).zip(&bag) {
+                let entry = &mut profile[(n - range.start()) as usize];
+                *entry = entry.max(likelihood);
+            }
+
+            let Some(index) = (0..bag.len()).find(|&i| bag[i] < *ranges[i].end()) else {
+                break;
+            };
+
+            bag[index] += 1;
+
+            for i in 0..index {
+                bag[i] = *ranges[i].start();
+            }
+        }
+
+        let threshold = best_likelihood - likelihood_drop(confidence);
+
+        Ok(BagEstimate {
+            most_likely: colors
+                .iter()
+                .zip(&best)
+                .map(|(&(color, _), &n)| (color, n))
+                .collect(),
+            intervals: colors
+                .iter()
+                .zip(profiles.iter().zip(&ranges))
+                .map(|(&(color, _), (profile, range))| {
+                    let plausible = profile
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &likelihood)| likelihood >= threshold)
+                        .map(|(offset, _)| range.start() + offset as u32);
+                    let low = plausible.clone().min().unwrap_or(*range.start());
+                    let high = plausible.max().unwrap_or(*range.end());
+
+                    (color.to_string(), low..=high)
+                })
+                .collect(),
+        })
+    }
+}
+
+impl Record {
+    pub fn estimate_bags(
+        &self,
+        max_per_color: u32,
+        confidence: f64,
+    ) -> Result<Vec<(u32, BagEstimate)>, EstimateError> {
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(EstimateError::InvalidConfidence(confidence));
+        }
+
+        self.games
+            .iter()
+            .map(|game| Ok((game.id, game.estimate_bag(max_per_color, confidence)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_record, estimate_bags};
+
+    use super::*;
+
+    fn interval(estimate: &BagEstimate, color: &str) -> RangeInclusive<u32> {
+        estimate
+            .intervals
+            .iter()
+            .find(|(name, _)| name == color)
+            .map(|(_, interval)| interval.clone())
+            .unwrap_or(0..=u32::MAX)
+    }
+
+    #[test]
+    fn intervals_contain_most_likely_bag() -> Result<(), Box<dyn Error>> {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 red, 1 blue; 2 red; 2 red; 1 red, 1 blue; 2 red
+Game 3: 2 green, 1 red; 1 blue; 3 green; 1 red, 1 green";
+
+        for confidence in [0.5, 0.9, 0.99] {
+            for (id, estimate) in build_record(input.as_bytes())?.estimate_bags(15, confidence)? {
+                for (color, count) in estimate.most_likely.iter() {
+                    assert!(
+                        interval(&estimate, color).contains(&count),
+                        "game {id} {color} at {confidence}"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn intervals_narrow_with_more_draws() -> Result<(), Box<dyn Error>> {
+        let input = "Game 1: 2 red, 1 blue
+Game 2: 2 red, 1 blue; 2 red, 1 blue; 2 red, 1 blue; 2 red, 1 blue; 2 red, 1 blue; 2 red, 1 blue";
+
+        let estimates = build_record(input.as_bytes())?.estimate_bags(30, 0.9)?;
+        let width = |index: usize, color| {
+            let interval = interval(&estimates[index].1, color);
+
+            interval.end() - interval.start()
+        };
+
+        for color in ["red", "blue"] {
+            assert!(width(1, color) < width(0, color), "{color}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_large_counts() -> Result<(), Box<dyn Error>> {
+        let estimates = estimate_bags(
+            "Game 1: 3000000000 red, 3000000000 blue; 1 red".as_bytes(),
+            10,
+            0.9,
+        )?;
+
+        assert_eq!(
+            vec![
+                (String::from("blue"), 3_000_000_000..=3_000_000_000),
+                (String::from("red"), 3_000_000_000..=3_000_000_000)
+            ],
+            estimates[0].1.intervals
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_large_searches() -> Result<(), Box<dyn Error>> {
+        let record =
+            build_record("Game 1: 1 red, 1 green, 1 blue, 1 cyan, 1 magenta, 1 yellow".as_bytes())?;
+
+        assert_eq!(
+            Some(String::from(
+                "game 1 has 113379904 candidate bags, at most 1048576 can be searched"
+            )),
+            record.estimate_bags(22, 0.9).err().map(|e| e.to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_confidence() -> Result<(), Box<dyn Error>> {
+        let record = build_record("Game 1: 3 blue, 4 red".as_bytes())?;
+
+        assert!(record.estimate_bags(10, 1.0).is_err());
+        assert!(record.estimate_bags(10, 0.0).is_err());
+        assert!(record.estimate_bags(10, f64::NAN).is_err());
+
+        Ok(())
+    }
+}
@@ -1,152 +1,274 @@
+use std::collections::BTreeMap;
 use std::error::Error;
-use std::fmt;
+use std::fmt::{self, Display};
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
-const MAX_RED: u32 = 12;
-const MAX_GREEN: u32 = 13;
-const MAX_BLUE: u32 = 14;
+use common::{next_token, parse_number, ParseError, ParseErrorKind, ParseMode, Solution};
 
-struct GameSet {
-    red_count: u32,
-    green_count: u32,
-    blue_count: u32,
+mod estimate;
+
+pub use estimate::{BagEstimate, EstimateError};
+
+const BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cubes {
+    counts: BTreeMap<String, u32>,
 }
 
 struct Game {
     id: u32,
-    sets: Vec<GameSet>,
+    sets: Vec<Cubes>,
+}
+
+pub struct Record {
+    games: Vec<Game>,
 }
 
 #[derive(Debug)]
-struct ParseGameError;
+pub struct OverflowError;
 
-impl fmt::Display for ParseGameError {
+impl fmt::Display for OverflowError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse game")
+        write!(f, "power does not fit in 128 bits")
     }
 }
 
-impl Error for ParseGameError {}
+impl Error for OverflowError {}
+
+impl<'a> FromIterator<(&'a str, u32)> for Cubes {
+    fn from_iter<I: IntoIterator<Item = (&'a str, u32)>>(iter: I) -> Self {
+        let mut cubes = Cubes::new();
+
+        for (color, count) in iter {
+            cubes.add(color, count);
+        }
+
+        cubes
+    }
+}
 
-impl FromStr for GameSet {
-    type Err = ParseGameError;
+impl FromStr for Cubes {
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut red_count = 0;
-        let mut green_count = 0;
-        let mut blue_count = 0;
+        Self::parse_with(s, ParseMode::Lenient)
+    }
+}
+
+impl fmt::Display for Cubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = self
+            .counts
+            .iter()
+            .map(|(color, count)| format!("{count} {color}"))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", counts.join(", "))
+    }
+}
+
+impl Cubes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
+        let mut cubes = Cubes::new();
 
         for color_count in s.split(", ") {
-            let mut parts = color_count.split(" ");
-
-            let count: u32 = parts
-                .next()
-                .ok_or(ParseGameError)?
-                .parse()
-                .map_err(|_| ParseGameError)?;
-            let color = parts.next().ok_or(ParseGameError)?;
-
-            match color {
-                "red" => red_count = count,
-                "green" => green_count = count,
-                "blue" => blue_count = count,
-                _ => return Err(ParseGameError),
+            let mut parts = color_count.split(' ');
+
+            let count = parse_number(next_token(&mut parts, "count", s)?, s)?;
+            let color = next_token(&mut parts, "color", color_count)
+                .map_err(|e| e.within(s, color_count))?;
+
+            if mode == ParseMode::Strict && cubes.counts.contains_key(color) {
+                return Err(ParseError::invalid("repeated color", s, color));
             }
+
+            cubes.add(color, count);
+        }
+
+        Ok(cubes)
+    }
+
+    pub fn add(&mut self, color: &str, count: u32) {
+        *self.counts.entry(color.to_string()).or_insert(0) += count;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn count(&self, color: &str) -> u32 {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.counts
+            .iter()
+            .map(|(color, &count)| (color.as_str(), count))
+    }
+
+    pub fn union(&self, other: &Cubes) -> Cubes {
+        let mut union = self.clone();
+
+        for (color, count) in other.iter() {
+            let entry = union.counts.entry(color.to_string()).or_insert(0);
+            *entry = (*entry).max(count);
         }
 
-        Ok(GameSet {
-            red_count,
-            green_count,
-            blue_count,
+        union
+    }
+
+    pub fn fits_in(&self, bag: &Cubes) -> bool {
+        self.iter().all(|(color, count)| count <= bag.count(color))
+    }
+
+    pub fn power(&self) -> Result<u128, OverflowError> {
+        self.counts.values().try_fold(1u128, |power, &count| {
+            power.checked_mul(count.into()).ok_or(OverflowError)
         })
     }
 }
 
+pub fn parse_limit(spec: &str) -> Result<(String, u32), ParseError> {
+    let mut parts = spec.split('=');
+
+    let color = next_token(&mut parts, "color", spec)?;
+    let count = parse_number(next_token(&mut parts, "count", spec)?, spec)?;
+
+    if color.is_empty() {
+        return Err(ParseError::new(
+            ParseErrorKind::Missing("color"),
+            spec,
+            &spec[..0],
+        ));
+    }
+
+    Ok((color.to_string(), count))
+}
+
 impl FromStr for Game {
-    type Err = ParseGameError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, ParseMode::Lenient)
+    }
+}
+
+impl Game {
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
         let mut parts = s.split(": ");
 
-        let mut id_parts = parts.next().ok_or(ParseGameError)?.split(" ");
-        id_parts.next().ok_or(ParseGameError)?;
+        let mut id_parts = next_token(&mut parts, "game id", s)?.split(' ');
+        next_token(&mut id_parts, "game id", s)?;
 
-        let id = id_parts
-            .next()
-            .ok_or(ParseGameError)?
-            .parse()
-            .map_err(|_| ParseGameError)?;
+        let id = parse_number(next_token(&mut id_parts, "game id", s)?, s)?;
 
-        let set_parts = parts.next().ok_or(ParseGameError)?;
+        let set_parts = next_token(&mut parts, "sets", s)?;
         let mut sets = vec![];
 
         for set in set_parts.split("; ") {
-            sets.push(set.parse()?);
+            sets.push(Cubes::parse_with(set, mode).map_err(|e| e.within(s, set))?);
         }
 
         Ok(Game { id, sets })
     }
-}
 
-impl GameSet {
-    fn is_possible(&self) -> bool {
-        self.red_count <= MAX_RED && self.green_count <= MAX_GREEN && self.blue_count <= MAX_BLUE
+    fn is_possible_with(&self, bag: &Cubes) -> bool {
+        self.sets.iter().all(|set| set.fits_in(bag))
+    }
+
+    fn minimal_bag(&self) -> Cubes {
+        self.sets
+            .iter()
+            .fold(Cubes::new(), |bag, set| bag.union(set))
     }
 }
 
-impl Game {
-    fn is_possible(&self) -> bool {
-        self.sets.iter().all(GameSet::is_possible)
+impl Record {
+    pub fn possible_games(&self, bag: &Cubes) -> Vec<u32> {
+        self.games
+            .iter()
+            .filter(|game| game.is_possible_with(bag))
+            .map(|game| game.id)
+            .collect()
     }
 
-    fn sum_power(&self) -> u32 {
-        let mut max_red = 1;
-        let mut max_green = 1;
-        let mut max_blue = 1;
+    pub fn minimal_bags(&self) -> Vec<(u32, Cubes)> {
+        self.games
+            .iter()
+            .map(|game| (game.id, game.minimal_bag()))
+            .collect()
+    }
 
-        for set in &self.sets {
-            if set.red_count > max_red {
-                max_red = set.red_count;
-            }
+    pub fn minimal_bag(&self) -> Cubes {
+        self.games
+            .iter()
+            .fold(Cubes::new(), |bag, game| bag.union(&game.minimal_bag()))
+    }
 
-            if set.green_count > max_green {
-                max_green = set.green_count;
-            }
+    pub fn is_possible_for_all(&self, bag: &Cubes) -> bool {
+        self.minimal_bag().fits_in(bag)
+    }
 
-            if set.blue_count > max_blue {
-                max_blue = set.blue_count;
-            }
-        }
+    fn sum_possible_games(&self, bag: &Cubes) -> u32 {
+        self.possible_games(bag).iter().sum()
+    }
 
-        max_red * max_green * max_blue
+    pub fn power_sets(&self) -> Result<u128, OverflowError> {
+        self.games.iter().try_fold(0u128, |sum, game| {
+            sum.checked_add(game.minimal_bag().power()?)
+                .ok_or(OverflowError)
+        })
     }
 }
 
-pub fn possible_games(input: impl Read) -> Result<u32, Box<dyn Error>> {
-    let mut sum = 0;
+impl Solution for Record {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut games = vec![];
 
-    for line in io::BufReader::new(input).lines() {
-        let game = line?.parse::<Game>()?;
-
-        if game.is_possible() {
-            sum += game.id;
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            games.push(Game::parse_with(&line?, mode).map_err(|e| e.at_line(index + 1))?);
         }
+
+        Ok(Self { games })
     }
 
-    Ok(sum)
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_possible_games(&default_bag()))
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.power_sets()?)
+    }
 }
 
-pub fn power_sets(input: impl Read) -> Result<u32, Box<dyn Error>> {
-    let mut sum = 0;
+pub fn default_bag() -> Cubes {
+    BAG.into_iter().collect()
+}
 
-    for line in io::BufReader::new(input).lines() {
-        let game = line?.parse::<Game>()?;
+pub fn possible_games(input: impl Read, bag: &Cubes) -> Result<u32, Box<dyn Error>> {
+    Ok(Record::parse(input)?.sum_possible_games(bag))
+}
 
-        sum += game.sum_power()
-    }
+pub fn power_sets(input: impl Read) -> Result<u128, Box<dyn Error>> {
+    Ok(Record::parse(input)?.power_sets()?)
+}
+
+pub fn build_record(input: impl Read) -> Result<Record, Box<dyn Error>> {
+    Record::parse(input)
+}
 
-    Ok(sum)
+pub fn estimate_bags(
+    input: impl Read,
+    max_per_color: u32,
+    confidence: f64,
+) -> Result<Vec<(u32, BagEstimate)>, Box<dyn Error>> {
+    Ok(Record::parse(input)?.estimate_bags(max_per_color, confidence)?)
 }
 
 #[cfg(test)]
@@ -161,7 +283,7 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
     fn sum_possible_games() -> Result<(), Box<dyn Error>> {
-        assert_eq!(8, possible_games(INPUT.as_bytes())?);
+        assert_eq!(8, possible_games(INPUT.as_bytes(), &default_bag())?);
 
         Ok(())
     }
@@ -169,7 +291,85 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
     #[test]
     fn sum_power_sets() -> Result<(), Box<dyn Error>> {
         assert_eq!(2286, power_sets(INPUT.as_bytes())?);
+        assert_eq!(
+            10_000_000_000,
+            power_sets("Game 1: 100 a, 100 b, 100 c, 100 d, 100 e".as_bytes())?
+        );
+
+        let colors = (0..20)
+            .map(|color| format!("100000 c{color}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        assert_eq!(
+            Some(String::from("power does not fit in 128 bits")),
+            power_sets(format!("Game 1: {colors}").as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_bags_with_any_colors() -> Result<(), Box<dyn Error>> {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 blue, 2 purple; 5 red";
+
+        let record = build_record(input.as_bytes())?;
+        let bag = [("red", 5), ("blue", 3)].into_iter().collect::<Cubes>();
+
+        assert_eq!(vec![1], record.possible_games(&bag));
+        assert_eq!(
+            vec![
+                (1, [("blue", 3), ("red", 4)].into_iter().collect()),
+                (
+                    2,
+                    [("blue", 1), ("purple", 2), ("red", 5)]
+                        .into_iter()
+                        .collect()
+                )
+            ],
+            record.minimal_bags()
+        );
+        assert_eq!("3 blue, 2 purple, 5 red", record.minimal_bag().to_string());
+        assert!(record.is_possible_for_all(&bag.union(&record.minimal_bag())));
+        assert!(!record.is_possible_for_all(&bag));
 
         Ok(())
     }
+
+    #[test]
+    fn parse_limits() -> Result<(), Box<dyn Error>> {
+        assert_eq!((String::from("purple"), 3), parse_limit("purple=3")?);
+        assert_eq!(
+            "invalid number `x` at column 5
+  red=x
+      ^",
+            parse_limit("red=x").unwrap_err().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_repeated_color_when_strict() {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 blue, 2 blue";
+
+        assert!(Record::parse(input.as_bytes()).is_ok());
+
+        let error = Record::parse_with(input.as_bytes(), ParseMode::Strict)
+            .err()
+            .map(|e| e.to_string());
+
+        assert_eq!(
+            Some(String::from(
+                "invalid repeated color `blue` at line 2, column 19
+  Game 2: 1 blue, 2 blue
+                    ^^^^"
+            )),
+            error
+        );
+    }
 }
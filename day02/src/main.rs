@@ -1,15 +1,53 @@
+use std::env;
 use std::error::Error;
 use std::fs::File;
 
 use day02::*;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let mut bag = Cubes::new();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--limit" => {
+                let (color, count) = parse_limit(&args.next().ok_or("missing value for limit")?)?;
+                bag.add(&color, count);
+            }
+            _ => {
+                return Err(format!(
+                    "unexpected argument `{arg}`\n\nUsage: day02 [--limit <color>=<count>]..."
+                )
+                .into())
+            }
+        }
+    }
+
+    if bag.is_empty() {
+        bag = default_bag();
+    }
+
+    let record = build_record(File::open("input.txt")?)?;
+
+    let games = record.possible_games(&bag);
+
     println!(
-        "Possible games: {}",
-        possible_games(File::open("input.txt")?)?
+        "Possible games with {bag}: {}",
+        games
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
     );
 
-    println!("Sum power sets: {}", power_sets(File::open("input.txt")?)?);
+    println!("Sum of possible game IDs: {}", games.iter().sum::<u32>());
+
+    println!("Sum power sets: {}", record.power_sets()?);
+
+    println!(
+        "Smallest bag making every game possible: {}",
+        record.minimal_bag()
+    );
 
     Ok(())
 }
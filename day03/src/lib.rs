@@ -1,191 +1,221 @@
+use std::collections::HashMap;
 use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
+use std::ops::Range;
 
-fn has_symbol(line: &Option<Vec<char>>, index: usize, width: usize, current: bool) -> bool {
-    if let Some(l) = line {
-        if (index > 0 && !l[index - 1].is_digit(10) && l[index - 1] != '.')
-            || (!current && !l[index].is_digit(10) && l[index] != '.')
-            || (index < width - 1 && !l[index + 1].is_digit(10) && l[index + 1] != '.')
-        {
-            return true;
-        }
-    }
+use common::{parse_number, ParseError, ParseMode, Solution};
 
-    false
-}
+mod rules;
 
-pub fn sum_part_numbers(input: impl Read) -> Result<u32, Box<dyn Error>> {
-    let mut lines = io::BufReader::new(input).lines();
+pub use rules::{Aggregation, OverflowError, Rules, SymbolClass, UnknownAggregationError};
 
-    let mut prev_line: Option<Vec<char>> = None;
-    let mut curr_line: Option<Vec<char>> = None;
-    let mut next_line: Option<Vec<char>>;
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub row: usize,
+    pub span: Range<usize>,
+}
 
-    let mut width = 0;
-    let mut number = 0;
-    let mut part_number = false;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub glyph: char,
+    pub pos: (usize, usize),
+}
 
-    let mut sum = 0;
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    symbol_neighbors: Vec<Vec<usize>>,
+    number_neighbors: Vec<Vec<usize>>,
+}
 
-    loop {
-        if let Some(line) = lines.next() {
-            next_line = Some(line?.chars().collect::<Vec<_>>());
-        } else {
-            next_line = None;
+impl Schematic {
+    fn new() -> Self {
+        Self {
+            numbers: vec![],
+            symbols: vec![],
+            symbol_neighbors: vec![],
+            number_neighbors: vec![],
         }
+    }
 
-        if let Some(ref line) = curr_line {
-            if width == 0 {
-                width = line.len();
-            }
+    fn parse_line(&mut self, row: usize, line: &str) -> Result<(), ParseError> {
+        let mut start = None;
 
-            for (index, char) in line.iter().enumerate() {
-                if char.is_digit(10) {
-                    number *= 10;
-                    number += char.to_digit(10).unwrap_or_default();
+        let glyphs = line.char_indices().chain([(line.len(), '.')]).enumerate();
 
-                    part_number = part_number
-                        || has_symbol(&prev_line, index, width, false)
-                        || has_symbol(&curr_line, index, width, true)
-                        || has_symbol(&next_line, index, width, false);
-                }
-
-                if !char.is_digit(10) || index == width - 1 {
-                    if part_number {
-                        sum += number;
-                    }
-
-                    number = 0;
-                    part_number = false;
-                }
+        for (x, (offset, c)) in glyphs {
+            if c.is_ascii_digit() {
+                start.get_or_insert((x, offset));
+                continue;
             }
 
-            number = 0;
-            part_number = false;
-        }
+            if let Some((start, start_offset)) = start.take() {
+                self.numbers.push(Number {
+                    value: parse_number(&line[start_offset..offset], line)?,
+                    row,
+                    span: start..x,
+                });
+            }
 
-        if next_line.is_none() {
-            break;
+            if c != '.' {
+                self.symbols.push(Symbol {
+                    glyph: c,
+                    pos: (x, row),
+                });
+            }
         }
 
-        prev_line = curr_line;
-        curr_line = next_line;
+        Ok(())
     }
 
-    Ok(sum)
-}
-
-fn number_at(line: &Option<Vec<char>>, index: usize, width: usize) -> Option<u32> {
-    if let Some(l) = line {
-        if !l[index].is_digit(10) {
-            return None;
-        }
-
-        let mut i = index;
-
-        loop {
-            if i == 0 {
-                break;
-            }
-
-            if l[i - 1].is_digit(10) {
-                i -= 1;
-            } else {
-                break;
+    fn index_neighbors(&mut self) {
+        let positions = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| (symbol.pos, index))
+            .collect::<HashMap<_, _>>();
+
+        self.symbol_neighbors = vec![vec![]; self.symbols.len()];
+        self.number_neighbors = vec![vec![]; self.numbers.len()];
+
+        for (index, number) in self.numbers.iter().enumerate() {
+            for y in number.row.saturating_sub(1)..=number.row + 1 {
+                for x in number.span.start.saturating_sub(1)..=number.span.end {
+                    if let Some(&symbol) = positions.get(&(x, y)) {
+                        self.symbol_neighbors[symbol].push(index);
+                        self.number_neighbors[index].push(symbol);
+                    }
+                }
             }
         }
+    }
 
-        let mut number = 0;
-
-        while i <= width - 1 && l[i].is_digit(10) {
-            number *= 10;
-            number += l[i].to_digit(10).unwrap_or_default();
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
 
-            i += 1;
-        }
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
 
-        return Some(number);
+    pub fn neighbors(&self) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols
+            .iter()
+            .zip(&self.symbol_neighbors)
+            .map(|(symbol, neighbors)| {
+                (
+                    symbol,
+                    neighbors
+                        .iter()
+                        .map(|&index| &self.numbers[index])
+                        .collect(),
+                )
+            })
     }
 
-    None
-}
+    pub fn part_numbers<'a>(
+        &'a self,
+        symbols: &'a SymbolClass,
+    ) -> impl Iterator<Item = &'a Number> + 'a {
+        self.numbers
+            .iter()
+            .zip(&self.number_neighbors)
+            .filter(move |(_, neighbors)| {
+                neighbors
+                    .iter()
+                    .any(|&symbol| symbols.contains(self.symbols[symbol].glyph))
+            })
+            .map(|(number, _)| number)
+    }
 
-fn numbers(line: &Option<Vec<char>>, index: usize, width: usize, current: bool) -> (u32, u32) {
-    if let Some(l) = line {
-        let mut product = 1;
-        let mut count = 0;
+    pub fn numbers_adjacent_to(&self, glyph: char) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.number_neighbors)
+            .filter(move |(_, neighbors)| {
+                neighbors
+                    .iter()
+                    .any(|&symbol| self.symbols[symbol].glyph == glyph)
+            })
+            .map(|(number, _)| number)
+    }
 
-        if index > 0 {
-            if let Some(l) = number_at(line, index - 1, width) {
-                product *= l;
-                count += 1;
-            }
-        }
+    pub fn gears<'a>(
+        &'a self,
+        rules: &'a Rules,
+    ) -> impl Iterator<Item = (&'a Symbol, Vec<&'a Number>)> + 'a {
+        self.neighbors().filter(|(symbol, numbers)| {
+            rules.gears.contains(&symbol.glyph) && numbers.len() == rules.neighbors
+        })
+    }
 
-        if !current && (index == 0 || !l[index - 1].is_digit(10)) {
-            if let Some(m) = number_at(line, index, width) {
-                product *= m;
-                count += 1;
-            }
-        }
+    pub fn gear_ratios<'a>(
+        &'a self,
+        rules: &'a Rules,
+    ) -> impl Iterator<Item = Result<u64, OverflowError>> + 'a {
+        self.gears(rules).map(|(_, numbers)| {
+            rules
+                .aggregation
+                .apply(numbers.iter().map(|number| number.value))
+        })
+    }
 
-        if index < width - 1 && !l[index].is_digit(10) {
-            if let Some(r) = number_at(line, index + 1, width) {
-                product *= r;
-                count += 1;
-            }
-        }
+    pub fn isolated_symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.neighbors()
+            .filter(|(_, numbers)| numbers.is_empty())
+            .map(|(symbol, _)| symbol)
+    }
 
-        return (product, count);
+    fn sum_part_numbers(&self, rules: &Rules) -> u32 {
+        self.part_numbers(&rules.symbols)
+            .map(|number| number.value)
+            .sum()
     }
 
-    (1, 0)
+    fn sum_gear_ratios(&self, rules: &Rules) -> Result<u64, OverflowError> {
+        self.gear_ratios(rules).try_fold(0u64, |sum, ratio| {
+            sum.checked_add(ratio?).ok_or(OverflowError)
+        })
+    }
 }
 
-pub fn sum_gear_ratios(input: impl Read) -> Result<u32, Box<dyn Error>> {
-    let mut lines = io::BufReader::new(input).lines();
-
-    let mut prev_line: Option<Vec<char>> = None;
-    let mut curr_line: Option<Vec<char>> = None;
-    let mut next_line: Option<Vec<char>>;
-
-    let mut width = 0;
-    let mut sum = 0;
+impl Solution for Schematic {
+    fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut schematic = Schematic::new();
 
-    loop {
-        if let Some(line) = lines.next() {
-            next_line = Some(line?.chars().collect::<Vec<_>>());
-        } else {
-            next_line = None;
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            schematic
+                .parse_line(index, &line?)
+                .map_err(|e| e.at_line(index + 1))?;
         }
 
-        if let Some(ref line) = curr_line {
-            if width == 0 {
-                width = line.len();
-            }
-
-            for (index, &char) in line.iter().enumerate() {
-                if char == '*' {
-                    let prev_numbers = numbers(&prev_line, index, width, false);
-                    let curr_numbers = numbers(&curr_line, index, width, true);
-                    let next_numbers = numbers(&next_line, index, width, false);
+        schematic.index_neighbors();
 
-                    if prev_numbers.1 + curr_numbers.1 + next_numbers.1 == 2 {
-                        sum += prev_numbers.0 * curr_numbers.0 * next_numbers.0;
-                    }
-                }
-            }
-        }
+        Ok(schematic)
+    }
 
-        if next_line.is_none() {
-            break;
-        }
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_part_numbers(&Rules::default()))
+    }
 
-        prev_line = curr_line;
-        curr_line = next_line;
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_gear_ratios(&Rules::default())?)
     }
+}
 
-    Ok(sum)
+pub fn sum_part_numbers(input: impl Read, rules: &Rules) -> Result<u32, Box<dyn Error>> {
+    Ok(Schematic::parse(input)?.sum_part_numbers(rules))
+}
+
+pub fn sum_gear_ratios(input: impl Read, rules: &Rules) -> Result<u64, Box<dyn Error>> {
+    Ok(Schematic::parse(input)?.sum_gear_ratios(rules)?)
+}
+
+pub fn build_schematic(input: impl Read) -> Result<Schematic, Box<dyn Error>> {
+    Schematic::parse(input)
 }
 
 #[cfg(test)]
@@ -205,14 +235,139 @@ mod tests {
 
     #[test]
     fn total_part_numbers() -> Result<(), Box<dyn Error>> {
-        assert_eq!(4361, sum_part_numbers(INPUT.as_bytes())?);
+        assert_eq!(4361, sum_part_numbers(INPUT.as_bytes(), &Rules::default())?);
 
         Ok(())
     }
 
     #[test]
     fn total_gear_ratios() -> Result<(), Box<dyn Error>> {
-        assert_eq!(467_835, sum_gear_ratios(INPUT.as_bytes())?);
+        assert_eq!(
+            467_835,
+            sum_gear_ratios(INPUT.as_bytes(), &Rules::default())?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_neighbors() -> Result<(), Box<dyn Error>> {
+        let schematic = build_schematic(INPUT.as_bytes())?;
+
+        assert_eq!(10, schematic.numbers().len());
+        assert_eq!(
+            Some(&Number {
+                value: 633,
+                row: 2,
+                span: 6..9,
+            }),
+            schematic.numbers_adjacent_to('#').next()
+        );
+        assert_eq!(
+            vec![(3, 4)],
+            schematic
+                .gears(&Rules {
+                    neighbors: 1,
+                    ..Rules::default()
+                })
+                .map(|(symbol, _)| symbol.pos)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, schematic.isolated_symbols().count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_custom_rules() -> Result<(), Box<dyn Error>> {
+        let schematic = build_schematic(INPUT.as_bytes())?;
+
+        assert_eq!(
+            4361 - 633 - 592,
+            schematic.sum_part_numbers(&Rules {
+                symbols: SymbolClass::Except(vec!['#', '+']),
+                ..Rules::default()
+            })
+        );
+        assert_eq!(
+            633 + 592,
+            schematic.sum_part_numbers(&Rules {
+                symbols: SymbolClass::Only(vec!['#', '+']),
+                ..Rules::default()
+            })
+        );
+
+        let rules = Rules {
+            gears: vec!['*', '+', '$'],
+            neighbors: 1,
+            aggregation: "max".parse()?,
+            ..Rules::default()
+        };
+
+        assert_eq!(
+            vec![617, 592, 664],
+            schematic
+                .gear_ratios(&rules)
+                .collect::<Result<Vec<_>, _>>()?
+        );
+        assert_eq!(
+            467 + 35 + 755 + 598,
+            schematic.sum_gear_ratios(&Rules {
+                aggregation: Aggregation::Sum,
+                ..Rules::default()
+            })?
+        );
+        assert!("mean".parse::<Aggregation>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiply_large_gear_ratios() -> Result<(), Box<dyn Error>> {
+        let rules = Rules {
+            neighbors: 4,
+            ..Rules::default()
+        };
+
+        assert_eq!(
+            996_005_996_001,
+            sum_gear_ratios("999.999\n...*...\n999.999".as_bytes(), &rules)?
+        );
+        assert_eq!(
+            Some(String::from("gear ratio does not fit in 64 bits")),
+            sum_gear_ratios(
+                "99999.99999\n99999*99999\n99999.99999".as_bytes(),
+                &Rules {
+                    neighbors: 6,
+                    ..Rules::default()
+                }
+            )
+            .err()
+            .map(|e| e.to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn handle_ragged_lines() -> Result<(), Box<dyn Error>> {
+        let schematic = build_schematic("12\n.€7\n3".as_bytes())?;
+
+        assert_eq!(
+            vec![12, 7, 3],
+            schematic
+                .part_numbers(&SymbolClass::Any)
+                .map(|number| number.value)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 1)],
+            schematic
+                .symbols()
+                .iter()
+                .map(|symbol| symbol.pos)
+                .collect::<Vec<_>>()
+        );
 
         Ok(())
     }
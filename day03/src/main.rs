@@ -1,17 +1,44 @@
+use std::env;
 use std::error::Error;
 use std::fs::File;
 
 use day03::*;
 
+const USAGE: &str = "Usage: day03 [--symbols <chars> | --except <chars>] [--gears <chars>] \
+                     [--neighbors <count>] [--aggregate <product|sum|max>]";
+
+fn parse_rules(mut args: impl Iterator<Item = String>) -> Result<Rules, Box<dyn Error>> {
+    let mut rules = Rules::default();
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {arg}\n\n{USAGE}"))?;
+
+        match arg.as_str() {
+            "--symbols" => rules.symbols = SymbolClass::Only(value.chars().collect()),
+            "--except" => rules.symbols = SymbolClass::Except(value.chars().collect()),
+            "--gears" => rules.gears = value.chars().collect(),
+            "--neighbors" => rules.neighbors = value.parse()?,
+            "--aggregate" => rules.aggregation = value.parse()?,
+            _ => return Err(format!("unexpected argument `{arg}`\n\n{USAGE}").into()),
+        }
+    }
+
+    Ok(rules)
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let rules = parse_rules(env::args().skip(1))?;
+
     println!(
         "Sum of part numbers: {}",
-        sum_part_numbers(File::open("input.txt")?)?
+        sum_part_numbers(File::open("input.txt")?, &rules)?
     );
 
     println!(
         "Sum of gear ratios: {}",
-        sum_gear_ratios(File::open("input.txt")?)?
+        sum_gear_ratios(File::open("input.txt")?, &rules)?
     );
 
     Ok(())
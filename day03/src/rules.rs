@@ -0,0 +1,99 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SymbolClass {
+    #[default]
+    Any,
+    Only(Vec<char>),
+    Except(Vec<char>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Product,
+    Sum,
+    Max,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub symbols: SymbolClass,
+    pub gears: Vec<char>,
+    pub neighbors: usize,
+    pub aggregation: Aggregation,
+}
+
+#[derive(Debug)]
+pub struct UnknownAggregationError(String);
+
+impl fmt::Display for UnknownAggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown aggregation `{}`, expected product, sum or max",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownAggregationError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gear ratio does not fit in 64 bits")
+    }
+}
+
+impl Error for OverflowError {}
+
+impl SymbolClass {
+    pub fn contains(&self, glyph: char) -> bool {
+        match self {
+            SymbolClass::Any => true,
+            SymbolClass::Only(glyphs) => glyphs.contains(&glyph),
+            SymbolClass::Except(glyphs) => !glyphs.contains(&glyph),
+        }
+    }
+}
+
+impl FromStr for Aggregation {
+    type Err = UnknownAggregationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Aggregation::Product),
+            "sum" => Ok(Aggregation::Sum),
+            "max" => Ok(Aggregation::Max),
+            _ => Err(UnknownAggregationError(s.to_string())),
+        }
+    }
+}
+
+impl Aggregation {
+    pub fn apply(&self, mut values: impl Iterator<Item = u32>) -> Result<u64, OverflowError> {
+        match self {
+            Aggregation::Product => values.try_fold(1u64, |product, value| {
+                product.checked_mul(value.into()).ok_or(OverflowError)
+            }),
+            Aggregation::Sum => Ok(values.map(u64::from).sum()),
+            Aggregation::Max => Ok(values.max().unwrap_or_default().into()),
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            symbols: SymbolClass::Any,
+            gears: vec!['*'],
+            neighbors: 2,
+            aggregation: Aggregation::Product,
+        }
+    }
+}
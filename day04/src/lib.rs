@@ -1,109 +1,200 @@
 use std::error::Error;
-use std::fmt;
+use std::fmt::{self, Display};
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
+use common::{next_token, parse_number_with, ParseError, ParseMode, Solution};
+
+enum NumberSet {
+    Dense { offset: u32, words: Vec<u64> },
+    Sparse(Vec<u32>),
+}
+
 struct Card {
-    winning_numbers: Vec<u32>,
-    numbers: Vec<u32>,
-    copies: u32,
+    matches: usize,
+}
+
+pub struct Pile {
+    cards: Vec<Card>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardCopies {
+    pub card: usize,
+    pub copies: u128,
+    pub won_from: Vec<(usize, u128)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ledger {
+    pub cards: Vec<CardCopies>,
 }
 
 #[derive(Debug)]
-struct ParseCardError;
+pub struct OverflowError;
 
-impl fmt::Display for ParseCardError {
+impl fmt::Display for OverflowError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse card")
+        write!(f, "total does not fit in 128 bits")
+    }
+}
+
+impl Error for OverflowError {}
+
+impl FromIterator<u32> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut numbers = iter.into_iter().collect::<Vec<_>>();
+        numbers.sort_unstable();
+        numbers.dedup();
+
+        let (Some(&offset), Some(&last)) = (numbers.first(), numbers.last()) else {
+            return NumberSet::Sparse(numbers);
+        };
+
+        if ((last - offset) / 64) as usize >= numbers.len() {
+            return NumberSet::Sparse(numbers);
+        }
+
+        let mut words = vec![0; ((last - offset) / 64) as usize + 1];
+
+        for number in numbers {
+            let index = number - offset;
+            words[index as usize / 64] |= 1 << (index % 64);
+        }
+
+        NumberSet::Dense { offset, words }
     }
 }
 
-impl Error for ParseCardError {}
+impl NumberSet {
+    fn contains(&self, number: u32) -> bool {
+        match self {
+            NumberSet::Dense { offset, words } => {
+                number.checked_sub(*offset).is_some_and(|index| {
+                    words
+                        .get(index as usize / 64)
+                        .is_some_and(|word| word & (1 << (index % 64)) != 0)
+                })
+            }
+            NumberSet::Sparse(numbers) => numbers.binary_search(&number).is_ok(),
+        }
+    }
+}
 
 impl FromStr for Card {
-    type Err = ParseCardError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, ParseMode::Lenient)
+    }
+}
+
+impl Card {
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
         let mut parts = s.split(": ");
-        parts.next().ok_or(ParseCardError)?;
+        next_token(&mut parts, "card id", s)?;
 
-        let mut number_parts = parts.next().ok_or(ParseCardError)?.split(" | ");
+        let mut number_parts = next_token(&mut parts, "numbers", s)?.split(" | ");
 
-        let winning_numbers = number_parts
-            .next()
-            .ok_or(ParseCardError)?
+        let winning_numbers = next_token(&mut number_parts, "winning numbers", s)?
             .split_ascii_whitespace()
-            .map(|i| i.parse().unwrap_or_default())
-            .collect();
+            .map(|i| parse_number_with(i, s, mode))
+            .collect::<Result<NumberSet, _>>()?;
 
-        let numbers = number_parts
-            .next()
-            .ok_or(ParseCardError)?
-            .split_ascii_whitespace()
-            .map(|i| i.parse().unwrap_or_default())
-            .collect();
+        let mut matches = 0;
 
-        Ok(Self {
-            winning_numbers,
-            numbers,
-            copies: 1,
-        })
+        for number in next_token(&mut number_parts, "numbers you have", s)?.split_ascii_whitespace()
+        {
+            if winning_numbers.contains(parse_number_with(number, s, mode)?) {
+                matches += 1;
+            }
+        }
+
+        Ok(Self { matches })
+    }
+
+    fn points(&self) -> Option<u128> {
+        match self.matches {
+            0 => Some(0),
+            matches => 1u128.checked_shl(u32::try_from(matches - 1).ok()?),
+        }
     }
 }
 
-impl Card {
-    fn points(&self) -> u32 {
-        self.numbers.iter().fold(0, |acc, n| {
-            if self.winning_numbers.contains(n) {
-                if acc == 0 {
-                    1
-                } else {
-                    acc * 2
-                }
-            } else {
-                acc
-            }
+impl Ledger {
+    pub fn total(&self) -> Result<u128, OverflowError> {
+        self.cards.iter().try_fold(0u128, |total, card| {
+            total.checked_add(card.copies).ok_or(OverflowError)
         })
     }
+}
 
-    fn matching_numbers(&self) -> u32 {
-        self.numbers
-            .iter()
-            .filter(|n| self.winning_numbers.contains(n))
-            .count() as u32
+impl Pile {
+    fn sum_points(&self) -> Result<u128, OverflowError> {
+        self.cards.iter().try_fold(0u128, |total, card| {
+            card.points()
+                .and_then(|points| total.checked_add(points))
+                .ok_or(OverflowError)
+        })
     }
-}
 
-pub fn sum_points(input: impl Read) -> Result<u32, Box<dyn Error>> {
-    let mut sum = 0;
+    pub fn ledger(&self) -> Result<Ledger, OverflowError> {
+        let mut cards = (1..=self.cards.len())
+            .map(|card| CardCopies {
+                card,
+                copies: 1,
+                won_from: vec![],
+            })
+            .collect::<Vec<_>>();
 
-    for line in io::BufReader::new(input).lines() {
-        let card = line?.parse::<Card>()?;
+        for (index, card) in self.cards.iter().enumerate() {
+            let copies = cards[index].copies;
+            let won = (index + 1)..(index + 1 + card.matches).min(cards.len());
 
-        sum += card.points();
+            for won in &mut cards[won] {
+                won.copies = won.copies.checked_add(copies).ok_or(OverflowError)?;
+                won.won_from.push((index + 1, copies));
+            }
+        }
+
+        Ok(Ledger { cards })
     }
 
-    Ok(sum)
+    fn total_cards(&self) -> Result<u128, OverflowError> {
+        self.ledger()?.total()
+    }
 }
 
-pub fn total_cards(input: impl Read) -> Result<u32, Box<dyn Error>> {
-    let mut cards = vec![];
+impl Solution for Pile {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut cards = vec![];
 
-    for line in io::BufReader::new(input).lines() {
-        cards.push(line?.parse::<Card>()?);
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            cards.push(Card::parse_with(&line?, mode).map_err(|e| e.at_line(index + 1))?);
+        }
+
+        Ok(Self { cards })
     }
 
-    for i in 0..cards.len() {
-        for c in (i + 1)..(i + 1 + cards[i].matching_numbers() as usize) {
-            if c >= cards.len() {
-                break;
-            }
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_points()?)
+    }
 
-            cards[c].copies += cards[i].copies;
-        }
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.total_cards()?)
     }
+}
+
+pub fn sum_points(input: impl Read) -> Result<u128, Box<dyn Error>> {
+    Ok(Pile::parse(input)?.sum_points()?)
+}
+
+pub fn total_cards(input: impl Read) -> Result<u128, Box<dyn Error>> {
+    Ok(Pile::parse(input)?.total_cards()?)
+}
 
-    Ok(cards.iter().map(|card| card.copies).sum())
+pub fn build_ledger(input: impl Read) -> Result<Ledger, Box<dyn Error>> {
+    Ok(Pile::parse(input)?.ledger()?)
 }
 
 #[cfg(test)]
@@ -130,4 +221,94 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
         Ok(())
     }
+
+    #[test]
+    fn record_copy_ledger() -> Result<(), Box<dyn Error>> {
+        let ledger = build_ledger(INPUT.as_bytes())?;
+
+        assert_eq!(
+            vec![1, 2, 4, 8, 14, 1],
+            ledger
+                .cards
+                .iter()
+                .map(|card| card.copies)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            CardCopies {
+                card: 4,
+                copies: 8,
+                won_from: vec![(1, 1), (2, 2), (3, 4)],
+            },
+            ledger.cards[3]
+        );
+
+        Ok(())
+    }
+
+    fn generated_cards(count: usize, numbers: usize, matches: usize) -> String {
+        let winning = (0..numbers).map(|n| n.to_string()).collect::<Vec<_>>();
+        let have = (numbers - matches..2 * numbers - matches)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>();
+
+        (1..=count)
+            .map(|card| format!("Card {card}: {} | {}", winning.join(" "), have.join(" ")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn count_beyond_u64() -> Result<(), Box<dyn Error>> {
+        let input = generated_cards(150, 300, 2);
+
+        assert_eq!(
+            68_330_027_629_092_351_019_822_533_679_294,
+            total_cards(input.as_bytes())?
+        );
+        assert!(total_cards(generated_cards(200, 300, 2).as_bytes()).is_err());
+        assert_eq!(
+            1 << 99,
+            sum_points(generated_cards(1, 300, 100).as_bytes())?
+        );
+        assert!(sum_points(generated_cards(1, 300, 200).as_bytes()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn match_large_numbers() -> Result<(), Box<dyn Error>> {
+        assert_eq!(1, total_cards("Card 1: 1 4000000000 | 1 2".as_bytes())?);
+        assert_eq!(
+            2,
+            sum_points("Card 1: 4000000001 4000000000 | 4000000000 4000000001 3".as_bytes())?
+        );
+        assert_eq!(
+            4,
+            sum_points(
+                "Card 1: 1000000 1000063 1000064 1000100 | 999999 1000000 1000064 1000100"
+                    .as_bytes()
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_number_when_strict() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 2O 16 61 | 61 30 68 82 17 32 24 19";
+
+        assert!(Pile::parse(input.as_bytes()).is_ok());
+        assert_eq!(
+            Some(String::from(
+                "invalid number `2O` at line 2, column 15
+  Card 2: 13 32 2O 16 61 | 61 30 68 82 17 32 24 19
+                ^^"
+            )),
+            Pile::parse_with(input.as_bytes(), ParseMode::Strict)
+                .err()
+                .map(|e| e.to_string())
+        );
+    }
 }
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Almanac, Map};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conversion {
+    pub source: String,
+    pub destination: String,
+    pub map: Map,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainError {
+    Broken { from: String, to: String },
+    Ambiguous { from: String, to: String },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::Broken { from, to } => {
+                write!(f, "no conversion chain from {from} to {to}")
+            }
+            ChainError::Ambiguous { from, to } => {
+                write!(f, "more than one conversion chain from {from} to {to}")
+            }
+        }
+    }
+}
+
+impl Error for ChainError {}
+
+fn search<'a>(
+    conversions: &'a [Conversion],
+    at: &'a str,
+    to: &str,
+    visited: &mut Vec<&'a str>,
+    path: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+) {
+    if at == to {
+        found.push(path.clone());
+        return;
+    }
+
+    visited.push(at);
+
+    for (index, conversion) in conversions.iter().enumerate() {
+        if found.len() > 1 {
+            break;
+        }
+
+        if conversion.source != at || visited.contains(&conversion.destination.as_str()) {
+            continue;
+        }
+
+        path.push(index);
+        search(
+            conversions,
+            &conversion.destination,
+            to,
+            visited,
+            path,
+            found,
+        );
+        path.pop();
+    }
+
+    visited.pop();
+}
+
+impl Almanac {
+    pub fn converter(&self, from: &str, to: &str) -> Result<Map, ChainError> {
+        let conversions = self.conversions();
+        let mut found = vec![];
+
+        search(conversions, from, to, &mut vec![], &mut vec![], &mut found);
+
+        match found.as_slice() {
+            [path] => Ok(path.iter().fold(Map::identity(), |chain, &index| {
+                chain.then(&conversions[index].map)
+            })),
+            [] => Err(ChainError::Broken {
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+            _ => Err(ChainError::Ambiguous {
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+        }
+    }
+}
@@ -1,64 +1,60 @@
 use std::error::Error;
-use std::fmt;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct SeedRange {
-    start: u64,
-    length: u64,
-}
+use common::{next_token, parse_number, parse_number_with, ParseError, ParseMode, Solution};
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct MapRange {
-    source_start: u64,
-    destination_start: u64,
-    length: u64,
-}
+mod category;
+mod map;
+mod validate;
 
-struct Map {
-    map_ranges: Vec<MapRange>,
-}
+pub use category::{ChainError, Conversion};
+pub use map::{Intervals, Map, MapRange, NotInvertibleError};
+pub use validate::{Diagnostic, DiagnosticKind, ValidationError};
+
+const SEED: &str = "seed";
+const LOCATION: &str = "location";
 
-struct Almanac {
-    seed_ranges: Vec<SeedRange>,
-    maps: Vec<Map>,
+struct Table {
+    source: String,
+    destination: String,
+    ranges: Vec<(usize, MapRange)>,
 }
 
-#[derive(Debug)]
-struct ParseAlmanacError;
+pub struct Almanac {
+    seeds: Vec<u64>,
+    tables: Vec<Table>,
+    conversions: Vec<Conversion>,
+    chain: Result<Map, ChainError>,
+}
 
-impl fmt::Display for ParseAlmanacError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse almanac")
+fn parse_header(s: &str) -> Result<(String, String), ParseError> {
+    let categories = s.strip_suffix(" map:").unwrap_or_default();
+
+    match categories.split_once("-to-") {
+        Some((source, destination))
+            if !source.is_empty()
+                && !destination.is_empty()
+                && !source.contains(char::is_whitespace)
+                && !destination.contains(char::is_whitespace) =>
+        {
+            Ok((source.to_string(), destination.to_string()))
+        }
+        _ => Err(ParseError::invalid("map header", s, s)),
     }
 }
 
-impl Error for ParseAlmanacError {}
-
 impl FromStr for MapRange {
-    type Err = ParseAlmanacError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split_ascii_whitespace();
 
-        let destination_start = parts
-            .next()
-            .ok_or(ParseAlmanacError)?
-            .parse()
-            .map_err(|_| ParseAlmanacError)?;
-
-        let source_start = parts
-            .next()
-            .ok_or(ParseAlmanacError)?
-            .parse()
-            .map_err(|_| ParseAlmanacError)?;
-
-        let length = parts
-            .next()
-            .ok_or(ParseAlmanacError)?
-            .parse()
-            .map_err(|_| ParseAlmanacError)?;
+        let destination_start =
+            parse_number(next_token(&mut parts, "destination range start", s)?, s)?;
+        let source_start = parse_number(next_token(&mut parts, "source range start", s)?, s)?;
+        let length = parse_number(next_token(&mut parts, "range length", s)?, s)?;
 
         Ok(Self {
             destination_start,
@@ -68,149 +64,161 @@ impl FromStr for MapRange {
     }
 }
 
-impl SeedRange {
-    fn new(start: u64, length: u64) -> Self {
-        Self { start, length }
-    }
-}
-
-impl Map {
-    fn new() -> Self {
-        Self { map_ranges: vec![] }
-    }
-
-    fn map_range(&self, seed_range: &SeedRange) -> Vec<SeedRange> {
-        let mut seed_ranges = vec![];
-        let mut index = seed_range.start;
-
-        let mut map_ranges = self
-            .map_ranges
-            .iter()
-            .skip_while(|range| range.source_start + range.length < seed_range.start);
-
-        let mut map_range = map_ranges.next();
-
-        loop {
-            let start;
-            let mut length;
-
-            if let Some(r) = map_range {
-                if r.source_start > index {
-                    start = index;
-                    length = r.source_start - index;
-                } else if r.source_start + r.length < seed_range.start + seed_range.length {
-                    start = r.destination_start + (index - r.source_start);
-                    length = r.source_start + r.length - index;
-
-                    map_range = map_ranges.next();
-                } else {
-                    start = r.destination_start + (index - r.source_start);
-                    length = seed_range.start + seed_range.length - index;
-                }
-            } else {
-                start = index;
-                length = seed_range.length - (index - seed_range.start);
-            }
-
-            if length > seed_range.start + seed_range.length - index {
-                length = seed_range.start + seed_range.length - index;
-            }
-
-            index += length;
-            seed_ranges.push(SeedRange::new(start, length));
-
-            if index >= seed_range.start + seed_range.length || map_range.is_none() {
-                break;
-            }
-        }
-
-        seed_ranges
-    }
-}
-
 impl Almanac {
     fn new() -> Self {
         Self {
-            seed_ranges: vec![],
-            maps: vec![],
+            seeds: vec![],
+            tables: vec![],
+            conversions: vec![],
+            chain: Ok(Map::identity()),
         }
     }
 
-    fn add_seeds(&mut self, s: &str, seed_ranges: bool) {
+    fn add_seeds(&mut self, s: &str, mode: ParseMode) -> Result<(), ParseError> {
         let mut parts = s.split(": ");
         parts.next().unwrap_or_default();
 
-        let mut seed_ranges = parts
+        let mut seeds = parts
             .next()
             .unwrap_or_default()
             .split_ascii_whitespace()
-            .map(|i| i.parse().unwrap_or_default())
-            .collect::<Vec<_>>()
+            .map(|i| parse_number_with(i, s, mode))
+            .collect::<Result<_, _>>()?;
+
+        self.seeds.append(&mut seeds);
+
+        Ok(())
+    }
+
+    fn seed_intervals(&self, seed_ranges: bool) -> Intervals {
+        self.seeds
             .chunks(if seed_ranges { 2 } else { 1 })
-            .map(|chunk| SeedRange {
-                start: chunk[0],
-                length: if seed_ranges { chunk[1] } else { 1 },
-            })
-            .collect();
+            .filter_map(|chunk| {
+                let length = if seed_ranges { chunk.get(1)? } else { &1 };
 
-        self.seed_ranges.append(&mut seed_ranges);
+                Some(chunk[0]..=chunk[0].saturating_add(length.checked_sub(1)?))
+            })
+            .collect()
     }
 
-    fn add_map(&mut self) {
-        self.maps.push(Map::new());
+    fn add_map(&mut self, s: &str) -> Result<(), ParseError> {
+        let (source, destination) = parse_header(s)?;
+
+        self.tables.push(Table {
+            source,
+            destination,
+            ranges: vec![],
+        });
+
+        Ok(())
     }
 
-    fn add_range(&mut self, range: MapRange) {
-        if let Some(m) = self.maps.last_mut() {
-            m.map_ranges.push(range);
-            m.map_ranges.sort();
+    fn add_range(&mut self, line: usize, range: MapRange) {
+        if let Some(table) = self.tables.last_mut() {
+            table.ranges.push((line, range));
         }
     }
 
-    fn min_range_start(&self) -> u64 {
-        self.seed_ranges
+    fn build_maps(&mut self) {
+        self.conversions = self
+            .tables
             .iter()
-            .map(|&seed_range| {
-                let mut seed_ranges = vec![seed_range];
-                for map in &self.maps {
-                    let mut new_ranges = vec![];
-
-                    for s in seed_ranges {
-                        let mut r = map.map_range(&s);
-                        new_ranges.append(&mut r);
-                    }
-
-                    seed_ranges = new_ranges;
-                }
-                seed_ranges
+            .map(|table| Conversion {
+                source: table.source.clone(),
+                destination: table.destination.clone(),
+                map: Map::new(table.ranges.iter().map(|&(_, range)| range)),
             })
-            .flatten()
-            .map(|r| r.start)
+            .collect();
+
+        self.chain = self.converter(SEED, LOCATION);
+    }
+
+    pub fn conversions(&self) -> &[Conversion] {
+        &self.conversions
+    }
+
+    pub fn chain(&self) -> Result<&Map, ChainError> {
+        self.chain.as_ref().map_err(Clone::clone)
+    }
+
+    pub fn convert_seeds(&self, to: &str, seed_ranges: bool) -> Result<Intervals, ChainError> {
+        Ok(self
+            .converter(SEED, to)?
+            .apply_intervals(&self.seed_intervals(seed_ranges)))
+    }
+
+    pub fn min_seed_for(
+        &self,
+        to: &str,
+        seed_ranges: bool,
+    ) -> Result<Option<(u64, u64)>, ChainError> {
+        Ok(self
+            .converter(SEED, to)?
+            .min_with_preimage(&self.seed_intervals(seed_ranges)))
+    }
+
+    fn min_range_start(&self, seed_ranges: bool) -> Result<u64, ChainError> {
+        Ok(self
+            .chain()?
+            .apply_intervals(&self.seed_intervals(seed_ranges))
             .min()
-            .unwrap_or_default()
+            .unwrap_or_default())
     }
 }
 
-pub fn min_location(input: impl Read, seed_ranges: bool) -> Result<u64, Box<dyn Error>> {
-    let mut almanac = Almanac::new();
+impl Solution for Almanac {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut almanac = Almanac::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+            if l.is_empty() {
+                continue;
+            }
 
-        if l.is_empty() {
-            continue;
+            if l.contains("seeds:") {
+                almanac
+                    .add_seeds(&l, mode)
+                    .map_err(|e| e.at_line(index + 1))?;
+            } else if l.contains("map:") {
+                almanac.add_map(&l).map_err(|e| e.at_line(index + 1))?;
+            } else {
+                almanac.add_range(
+                    index + 1,
+                    l.parse().map_err(|e: ParseError| e.at_line(index + 1))?,
+                );
+            }
         }
 
-        if l.contains("seeds:") {
-            almanac.add_seeds(&l, seed_ranges);
-        } else if l.contains("map:") {
-            almanac.add_map();
-        } else {
-            almanac.add_range(l.parse()?);
+        if mode == ParseMode::Strict {
+            let diagnostics = almanac.diagnostics();
+
+            if !diagnostics.is_empty() {
+                return Err(ValidationError(diagnostics).into());
+            }
         }
+
+        almanac.build_maps();
+
+        Ok(almanac)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.min_range_start(false)?)
     }
 
-    Ok(almanac.min_range_start())
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.min_range_start(true)?)
+    }
+}
+
+pub fn min_location(input: impl Read, seed_ranges: bool) -> Result<u64, Box<dyn Error>> {
+    Ok(Almanac::parse(input)?.min_range_start(seed_ranges)?)
+}
+
+pub fn build_almanac(input: impl Read) -> Result<Almanac, Box<dyn Error>> {
+    Almanac::parse(input)
 }
 
 #[cfg(test)]
@@ -264,4 +272,106 @@ humidity-to-location map:
 
         Ok(())
     }
+
+    #[test]
+    fn chain_maps_into_one() -> Result<(), Box<dyn Error>> {
+        let almanac = build_almanac(INPUT.as_bytes())?;
+
+        for seed in 0..200 {
+            assert_eq!(
+                almanac
+                    .conversions()
+                    .iter()
+                    .fold(seed, |value, conversion| conversion.map.apply(value)),
+                almanac.chain()?.apply(seed)
+            );
+        }
+
+        assert_eq!(82, almanac.chain()?.invert()?.apply(46));
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_seeds_to_any_category() -> Result<(), Box<dyn Error>> {
+        let almanac = build_almanac(INPUT.as_bytes())?;
+
+        assert_eq!(
+            vec![35..=35, 43..=43, 78..=78, 82..=82],
+            almanac
+                .convert_seeds("humidity", false)?
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![13..=14, 55..=55, 79..=79],
+            almanac
+                .convert_seeds(SEED, false)?
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some((13, 41)), almanac.min_seed_for("water", false)?);
+        assert_eq!(Some((82, 46)), almanac.min_seed_for(LOCATION, true)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_maps_out_of_order() -> Result<(), Box<dyn Error>> {
+        let (seeds, maps) = INPUT.split_once("\n\n").unwrap_or_default();
+        let mut maps = maps.split("\n\n").collect::<Vec<_>>();
+        maps.reverse();
+
+        let input = format!("{seeds}\n\n{}", maps.join("\n\n"));
+
+        assert_eq!(35, min_location(input.as_bytes(), false)?);
+        assert_eq!(46, min_location(input.as_bytes(), true)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_broken_and_ambiguous_chains() -> Result<(), Box<dyn Error>> {
+        let almanac = build_almanac(INPUT.as_bytes())?;
+
+        assert_eq!(
+            Err(ChainError::Broken {
+                from: String::from("seed"),
+                to: String::from("gold"),
+            }),
+            almanac.convert_seeds("gold", false)
+        );
+
+        let broken = INPUT.replace("water-to-light", "water-to-lamp");
+
+        assert_eq!(
+            Some(String::from("no conversion chain from seed to location")),
+            min_location(broken.as_bytes(), false)
+                .err()
+                .map(|e| e.to_string())
+        );
+
+        let ambiguous = format!("{INPUT}\n\nseed-to-water map:\n0 0 1");
+
+        assert_eq!(
+            Some(String::from(
+                "more than one conversion chain from seed to location"
+            )),
+            min_location(ambiguous.as_bytes(), false)
+                .err()
+                .map(|e| e.to_string())
+        );
+        assert_eq!(
+            Some(String::from(
+                "invalid map header `seed to soil map:` at line 3, column 1
+  seed to soil map:
+  ^^^^^^^^^^^^^^^^^"
+            )),
+            build_almanac(INPUT.replace("seed-to-soil", "seed to soil").as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,342 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MapRange {
+    pub source_start: u64,
+    pub destination_start: u64,
+    pub length: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Segment {
+    start: u64,
+    offset: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Map {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Intervals {
+    ranges: Vec<(u64, u64)>,
+}
+
+#[derive(Debug)]
+pub struct NotInvertibleError;
+
+impl fmt::Display for NotInvertibleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "map is not a bijection and cannot be inverted")
+    }
+}
+
+impl Error for NotInvertibleError {}
+
+fn shift(value: u64, offset: i128) -> u64 {
+    (value as i128 + offset) as u64
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Map {
+    pub fn identity() -> Self {
+        Self {
+            segments: vec![Segment {
+                start: 0,
+                offset: 0,
+            }],
+        }
+    }
+
+    pub fn new(ranges: impl IntoIterator<Item = MapRange>) -> Self {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|range| range.length > 0)
+            .collect::<Vec<_>>();
+        ranges.sort();
+
+        let mut segments = vec![];
+        let mut next = 0u128;
+
+        for range in ranges {
+            let start = u128::from(range.source_start).max(next);
+            let end = (u128::from(range.source_start) + u128::from(range.length)).min(
+                u128::from(range.source_start) + (1 << 64) - u128::from(range.destination_start),
+            );
+
+            if start >= end {
+                continue;
+            }
+
+            if start > next {
+                segments.push(Segment {
+                    start: next as u64,
+                    offset: 0,
+                });
+            }
+
+            segments.push(Segment {
+                start: start as u64,
+                offset: i128::from(range.destination_start) - i128::from(range.source_start),
+            });
+
+            next = end;
+        }
+
+        if next <= u128::from(u64::MAX) {
+            segments.push(Segment {
+                start: next as u64,
+                offset: 0,
+            });
+        }
+
+        Self::from_segments(segments)
+    }
+
+    fn from_segments(segments: Vec<Segment>) -> Self {
+        let mut merged: Vec<Segment> = vec![];
+
+        for segment in segments {
+            if merged
+                .last()
+                .is_none_or(|last| last.offset != segment.offset)
+            {
+                merged.push(segment);
+            }
+        }
+
+        Self { segments: merged }
+    }
+
+    fn pieces_from(&self, first: usize) -> impl Iterator<Item = (u64, u64, i128)> + '_ {
+        self.segments[first..]
+            .iter()
+            .enumerate()
+            .map(move |(index, segment)| {
+                let end = self
+                    .segments
+                    .get(first + index + 1)
+                    .map_or(u64::MAX, |next| next.start - 1);
+
+                (segment.start, end, segment.offset)
+            })
+    }
+
+    fn pieces(&self) -> impl Iterator<Item = (u64, u64, i128)> + '_ {
+        self.pieces_from(0)
+    }
+
+    fn segment_index(&self, value: u64) -> usize {
+        self.segments
+            .partition_point(|segment| segment.start <= value)
+            - 1
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        shift(value, self.segments[self.segment_index(value)].offset)
+    }
+
+    pub fn then(&self, other: &Map) -> Map {
+        let mut segments = vec![];
+
+        for (start, end, offset) in self.pieces() {
+            let (image_start, image_end) = (shift(start, offset), shift(end, offset));
+            let mut index = other.segment_index(image_start);
+
+            loop {
+                let next = other.segments[index];
+                let from = next.start.max(image_start);
+
+                segments.push(Segment {
+                    start: shift(from, -offset),
+                    offset: offset + next.offset,
+                });
+
+                match other.segments.get(index + 1) {
+                    Some(following) if following.start <= image_end => index += 1,
+                    _ => break,
+                }
+            }
+        }
+
+        Self::from_segments(segments)
+    }
+
+    pub fn invert(&self) -> Result<Map, NotInvertibleError> {
+        let mut images = self
+            .pieces()
+            .map(|(start, end, offset)| (shift(start, offset), shift(end, offset), -offset))
+            .collect::<Vec<_>>();
+        images.sort();
+
+        let mut next = 0u128;
+
+        for &(start, end, _) in &images {
+            if u128::from(start) != next {
+                return Err(NotInvertibleError);
+            }
+
+            next = u128::from(end) + 1;
+        }
+
+        Ok(Self::from_segments(
+            images
+                .into_iter()
+                .map(|(start, _, offset)| Segment { start, offset })
+                .collect(),
+        ))
+    }
+
+    fn overlaps<'a>(
+        &'a self,
+        intervals: &'a Intervals,
+    ) -> impl Iterator<Item = (u64, u64, i128)> + 'a {
+        intervals.ranges.iter().flat_map(move |&(start, end)| {
+            self.pieces_from(self.segment_index(start))
+                .take_while(move |&(from, _, _)| from <= end)
+                .map(move |(from, to, offset)| (from.max(start), to.min(end), offset))
+        })
+    }
+
+    pub fn apply_intervals(&self, intervals: &Intervals) -> Intervals {
+        self.overlaps(intervals)
+            .map(|(start, end, offset)| shift(start, offset)..=shift(end, offset))
+            .collect()
+    }
+
+    pub fn min_with_preimage(&self, intervals: &Intervals) -> Option<(u64, u64)> {
+        self.overlaps(intervals)
+            .map(|(start, _, offset)| (start, shift(start, offset)))
+            .min_by_key(|&(_, value)| value)
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for Intervals {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(iter: I) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .map(|range| (*range.start(), *range.end()))
+            .collect::<Vec<_>>();
+        ranges.sort();
+
+        let mut merged: Vec<(u64, u64)> = vec![];
+
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+}
+
+impl Intervals {
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|&(start, _)| start)
+    }
+
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| u128::from(end - start) + 1)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(ranges: &[(u64, u64, u64)]) -> Map {
+        Map::new(
+            ranges
+                .iter()
+                .map(|&(destination_start, source_start, length)| MapRange {
+                    source_start,
+                    destination_start,
+                    length,
+                }),
+        )
+    }
+
+    #[test]
+    fn compose_maps() {
+        let seed_to_soil = map(&[(50, 98, 2), (52, 50, 48)]);
+        let soil_to_fertilizer = map(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+        let composed = seed_to_soil.then(&soil_to_fertilizer);
+
+        for seed in (0..120).chain([u64::MAX - 1, u64::MAX]) {
+            assert_eq!(
+                soil_to_fertilizer.apply(seed_to_soil.apply(seed)),
+                composed.apply(seed)
+            );
+        }
+
+        assert_eq!(seed_to_soil, seed_to_soil.then(&Map::identity()));
+        assert_eq!(Map::identity(), map(&[(7, 7, 3)]));
+
+        let overflowing = map(&[(u64::MAX - 1, 10, 5), (0, u64::MAX - 1, 5)]);
+
+        assert_eq!(u64::MAX, overflowing.apply(11));
+        assert_eq!(12, overflowing.apply(12));
+        assert_eq!(1, overflowing.apply(u64::MAX));
+    }
+
+    #[test]
+    fn invert_bijection() -> Result<(), Box<dyn Error>> {
+        let seed_to_soil = map(&[(50, 98, 2), (52, 50, 48)]);
+        let inverse = seed_to_soil.invert()?;
+
+        for seed in 0..120 {
+            assert_eq!(seed, inverse.apply(seed_to_soil.apply(seed)));
+        }
+
+        assert_eq!(Map::identity(), seed_to_soil.then(&inverse));
+        assert!(map(&[(0, 10, 5)]).invert().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn map_intervals() {
+        let seed_to_soil = map(&[(50, 98, 2), (52, 50, 48)]);
+        let seeds = [79..=92, 55..=67, 96..=101]
+            .into_iter()
+            .collect::<Intervals>();
+
+        assert_eq!(
+            vec![50..=51, 57..=69, 81..=94, 98..=99, 100..=101]
+                .into_iter()
+                .collect::<Intervals>(),
+            seed_to_soil.apply_intervals(&seeds)
+        );
+        assert_eq!(
+            vec![50..=51, 57..=69, 81..=94, 98..=101],
+            seed_to_soil
+                .apply_intervals(&seeds)
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(seeds.len(), seed_to_soil.apply_intervals(&seeds).len());
+        assert_eq!(Some((98, 50)), seed_to_soil.min_with_preimage(&seeds));
+    }
+}
@@ -0,0 +1,203 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Almanac, MapRange};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiagnosticKind {
+    ZeroLength,
+    SourceOverflow,
+    DestinationOverflow,
+    OverlappingSource { line: usize },
+    OverlappingDestination { line: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub map: String,
+    pub kind: DiagnosticKind,
+}
+
+#[derive(Debug)]
+pub struct ValidationError(pub Vec<Diagnostic>);
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::ZeroLength => write!(f, "range has zero length"),
+            DiagnosticKind::SourceOverflow => write!(f, "source range overflows u64"),
+            DiagnosticKind::DestinationOverflow => write!(f, "destination range overflows u64"),
+            DiagnosticKind::OverlappingSource { line } => {
+                write!(f, "source range overlaps line {line}")
+            }
+            DiagnosticKind::OverlappingDestination { line } => {
+                write!(f, "destination range overlaps line {line}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} ({} map): {}", self.line, self.map, self.kind)
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "almanac has {} problem(s)", self.0.len())?;
+
+        for diagnostic in &self.0 {
+            write!(f, "\n  {diagnostic}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ValidationError {}
+
+fn overlaps(ranges: &[(usize, u64, u64)]) -> Vec<(usize, usize)> {
+    let mut ranges = ranges
+        .iter()
+        .filter(|&&(_, _, length)| length > 0)
+        .map(|&(line, start, length)| (start, line, u128::from(start) + u128::from(length)))
+        .collect::<Vec<_>>();
+    ranges.sort();
+
+    let mut found = vec![];
+    let mut furthest: Option<(u128, usize)> = None;
+
+    for (start, line, end) in ranges {
+        match furthest {
+            Some((reach, other)) if u128::from(start) < reach => {
+                found.push((line, other));
+
+                if end > reach {
+                    furthest = Some((end, line));
+                }
+            }
+            _ => furthest = Some((end, line)),
+        }
+    }
+
+    found
+}
+
+impl Almanac {
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        for table in &self.tables {
+            let map = format!("{}-to-{}", table.source, table.destination);
+            let mut report = |line, kind| {
+                diagnostics.push(Diagnostic {
+                    line,
+                    map: map.clone(),
+                    kind,
+                })
+            };
+
+            for &(line, range) in &table.ranges {
+                let MapRange {
+                    source_start,
+                    destination_start,
+                    length,
+                } = range;
+
+                if length == 0 {
+                    report(line, DiagnosticKind::ZeroLength);
+                }
+
+                if source_start.checked_add(length).is_none() {
+                    report(line, DiagnosticKind::SourceOverflow);
+                }
+
+                if destination_start.checked_add(length).is_none() {
+                    report(line, DiagnosticKind::DestinationOverflow);
+                }
+            }
+
+            let sources = table
+                .ranges
+                .iter()
+                .map(|&(line, range)| (line, range.source_start, range.length))
+                .collect::<Vec<_>>();
+            let destinations = table
+                .ranges
+                .iter()
+                .map(|&(line, range)| (line, range.destination_start, range.length))
+                .collect::<Vec<_>>();
+
+            for (line, other) in overlaps(&sources) {
+                report(line, DiagnosticKind::OverlappingSource { line: other });
+            }
+
+            for (line, other) in overlaps(&destinations) {
+                report(line, DiagnosticKind::OverlappingDestination { line: other });
+            }
+        }
+
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.kind));
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{ParseMode, Solution};
+
+    use super::*;
+
+    const INPUT: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+60 99 5
+0 0 0
+
+soil-to-location map:
+0 15 37
+18446744073709551610 52 10
+0 0 15";
+
+    #[test]
+    fn report_range_problems() -> Result<(), Box<dyn Error>> {
+        let almanac = Almanac::parse(INPUT.as_bytes())?;
+
+        assert_eq!(
+            vec![
+                "line 6 (seed-to-soil map): source range overlaps line 4",
+                "line 6 (seed-to-soil map): destination range overlaps line 5",
+                "line 7 (seed-to-soil map): range has zero length",
+                "line 11 (soil-to-location map): destination range overflows u64",
+                "line 12 (soil-to-location map): destination range overlaps line 10",
+            ],
+            almanac
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.to_string())
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_problems_in_strict_mode() {
+        assert_eq!(
+            Some(String::from(
+                "almanac has 1 problem(s)
+  line 4 (seed-to-soil map): range has zero length"
+            )),
+            Almanac::parse_with(
+                "seeds: 1\n\nseed-to-soil map:\n0 0 0".as_bytes(),
+                ParseMode::Strict
+            )
+            .err()
+            .map(|e| e.to_string())
+        );
+    }
+}
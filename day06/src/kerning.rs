@@ -0,0 +1,163 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Kerning {
+    #[default]
+    Separate,
+    All,
+    Groups(usize),
+    Columns(Vec<usize>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KerningError {
+    Unknown(String),
+    ColumnOutOfRange { column: usize, columns: usize },
+    DuplicateColumn(usize),
+    NoColumns,
+    NotANumber(String),
+    TooLong(String),
+}
+
+impl fmt::Display for KerningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KerningError::Unknown(kerning) => write!(
+                f,
+                "unknown kerning `{kerning}`, expected separate, all, groups=N or columns=I,J,..."
+            ),
+            KerningError::ColumnOutOfRange { column, columns } => {
+                write!(f, "column {column} is out of range for {columns} column(s)")
+            }
+            KerningError::DuplicateColumn(column) => {
+                write!(f, "column {column} is selected more than once")
+            }
+            KerningError::NoColumns => write!(f, "no columns to join"),
+            KerningError::NotANumber(number) => {
+                write!(f, "joined number `{number}` is not a number")
+            }
+            KerningError::TooLong(number) => {
+                write!(f, "joined number `{number}` does not fit in 128 bits")
+            }
+        }
+    }
+}
+
+impl Error for KerningError {}
+
+impl FromStr for Kerning {
+    type Err = KerningError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || KerningError::Unknown(s.to_string());
+
+        match s.split_once('=') {
+            None if s == "separate" => Ok(Kerning::Separate),
+            None if s == "all" => Ok(Kerning::All),
+            Some(("groups", size)) => match size.parse() {
+                Ok(size) if size > 0 => Ok(Kerning::Groups(size)),
+                _ => Err(unknown()),
+            },
+            Some(("columns", columns)) => {
+                let columns = columns
+                    .split(',')
+                    .map(|column| column.parse().map_err(|_| unknown()))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                duplicate_column(&columns).map_or(Ok(Kerning::Columns(columns)), Err)
+            }
+            _ => Err(unknown()),
+        }
+    }
+}
+
+fn duplicate_column(columns: &[usize]) -> Option<KerningError> {
+    columns
+        .iter()
+        .enumerate()
+        .find(|&(index, column)| columns[..index].contains(column))
+        .map(|(_, &column)| KerningError::DuplicateColumn(column))
+}
+
+impl Kerning {
+    pub fn join(&self, columns: &[String]) -> Result<Vec<String>, KerningError> {
+        match self {
+            Kerning::Separate => Ok(columns.to_vec()),
+            Kerning::All => Ok(vec![columns.concat()]),
+            Kerning::Groups(size) => Ok(columns
+                .chunks((*size).max(1))
+                .map(|group| group.concat())
+                .collect()),
+            Kerning::Columns(selected) => {
+                if let Some(error) = duplicate_column(selected) {
+                    return Err(error);
+                }
+
+                if let Some(&column) = selected.iter().find(|&&column| column >= columns.len()) {
+                    return Err(KerningError::ColumnOutOfRange {
+                        column,
+                        columns: columns.len(),
+                    });
+                }
+
+                let first = selected.iter().min();
+                let mut joined = vec![];
+
+                for (index, column) in columns.iter().enumerate() {
+                    if first == Some(&index) {
+                        joined.push(
+                            selected
+                                .iter()
+                                .map(|&column| columns[column].as_str())
+                                .collect(),
+                        );
+                    } else if !selected.contains(&index) {
+                        joined.push(column.clone());
+                    }
+                }
+
+                Ok(joined)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_columns() -> Result<(), Box<dyn Error>> {
+        let columns = ["7", "15", "30", "4"].map(String::from);
+
+        assert_eq!(vec!["715304"], "all".parse::<Kerning>()?.join(&columns)?);
+        assert_eq!(
+            vec!["715", "304"],
+            "groups=2".parse::<Kerning>()?.join(&columns)?
+        );
+        assert_eq!(
+            vec!["7", "3015", "4"],
+            "columns=2,1".parse::<Kerning>()?.join(&columns)?
+        );
+        assert_eq!(
+            Err(KerningError::ColumnOutOfRange {
+                column: 4,
+                columns: 4
+            }),
+            Kerning::Columns(vec![0, 4]).join(&columns)
+        );
+        assert_eq!(
+            Err(KerningError::DuplicateColumn(1)),
+            "columns=1,2,1".parse::<Kerning>()
+        );
+        assert_eq!(
+            Err(KerningError::DuplicateColumn(0)),
+            Kerning::Columns(vec![0, 0]).join(&columns)
+        );
+        assert!("groups=0".parse::<Kerning>().is_err());
+
+        Ok(())
+    }
+}
@@ -1,74 +1,277 @@
 use std::error::Error;
+use std::fmt::{self, Display};
 use std::io::{self, BufRead, Read};
+use std::num::{IntErrorKind, ParseIntError};
+use std::ops::{Range, RangeInclusive};
 
-struct Race {
-    time: u64,
-    distance: u64,
+use common::{parse_number, ParseError, ParseErrorKind, ParseMode, Solution};
+
+mod kerning;
+mod model;
+
+pub use kerning::{Kerning, KerningError};
+pub use model::{Acceleration, ModelError, Strategy};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time: u128,
+    pub distance: u128,
+}
+
+pub struct RaceSheet {
+    times: Vec<String>,
+    distances: Vec<String>,
+}
+
+struct Row {
+    line: usize,
+    text: String,
+    spans: Vec<Range<usize>>,
 }
 
+#[derive(Debug)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "result does not fit in 128 bits")
+    }
+}
+
+impl Error for OverflowError {}
+
 impl Race {
-    fn new(time: u64, distance: u64) -> Self {
+    pub fn new(time: u128, distance: u128) -> Self {
         Self { time, distance }
     }
 
-    fn ways(&self) -> u64 {
-        let b = self.time as f64;
-        let c = self.distance as f64 + 0.5;
-        let d = (b * b - 4.0 * c).sqrt();
-        let min = (-b + d) / -2.0;
-        let max = (-b - d) / -2.0;
+    fn wins_with(&self, hold: u128) -> bool {
+        hold.checked_mul(self.time - hold)
+            .is_none_or(|distance| distance > self.distance)
+    }
+
+    pub fn winning_holds(&self) -> Option<RangeInclusive<u128>> {
+        let (mut low, mut high) = (0, self.time / 2);
+
+        if !self.wins_with(high) {
+            return None;
+        }
+
+        while low < high {
+            let middle = low + (high - low) / 2;
+
+            if self.wins_with(middle) {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+
+        Some(low..=self.time - low)
+    }
 
-        (max.floor() - min.ceil() + 1.0) as u64
+    pub fn ways(&self) -> u128 {
+        self.winning_holds()
+            .map_or(0, |holds| holds.end() - holds.start() + 1)
     }
 }
 
-pub fn total_ways(input: impl Read, join: bool) -> Result<u64, Box<dyn Error>> {
-    let mut times = vec![];
-    let mut distances = vec![];
+fn column_spans(text: &str, start: usize) -> Vec<Range<usize>> {
+    let mut spans: Vec<Range<usize>> = vec![];
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+    for (index, c) in text.char_indices().skip_while(|&(index, _)| index < start) {
+        if c.is_ascii_whitespace() {
+            continue;
+        }
 
-        if l.starts_with("Time:") {
-            let mut parts = l.split(":");
-            parts.next();
-            let times_parts = parts.next().unwrap_or_default().split_ascii_whitespace();
+        match spans.last_mut() {
+            Some(span) if span.end == index => span.end = index + c.len_utf8(),
+            _ => spans.push(index..index + c.len_utf8()),
+        }
+    }
 
-            if join {
-                times = vec![times_parts.collect::<String>().parse().unwrap_or_default()];
-            } else {
-                times = times_parts
-                    .map(|number| number.parse().unwrap_or_default())
-                    .collect();
+    spans
+}
+
+fn parse_joined(number: String) -> Result<u128, KerningError> {
+    number.parse().map_err(|e: ParseIntError| match e.kind() {
+        IntErrorKind::Empty => KerningError::NoColumns,
+        IntErrorKind::PosOverflow => KerningError::TooLong(number),
+        _ => KerningError::NotANumber(number),
+    })
+}
+
+impl Row {
+    fn parse(l: String, line: usize) -> Result<(String, Self), ParseError> {
+        let Some((label, _)) = l.split_once(':') else {
+            return Err(ParseError::missing("`:` after row label", &l).at_line(line));
+        };
+
+        let spans = column_spans(&l, label.len() + 1);
+
+        for span in &spans {
+            parse_number::<u128>(&l[span.clone()], &l).map_err(|e| e.at_line(line))?;
+        }
+
+        Ok((
+            label.to_string(),
+            Self {
+                line,
+                text: l,
+                spans,
+            },
+        ))
+    }
+
+    fn columns(&self) -> Vec<String> {
+        self.spans
+            .iter()
+            .map(|span| self.text[span.clone()].to_string())
+            .collect()
+    }
+}
+
+impl RaceSheet {
+    pub fn races(&self, kerning: &Kerning) -> Result<Vec<Race>, KerningError> {
+        kerning
+            .join(&self.times)?
+            .into_iter()
+            .zip(kerning.join(&self.distances)?)
+            .map(|(time, distance)| Ok(Race::new(parse_joined(time)?, parse_joined(distance)?)))
+            .collect()
+    }
+
+    pub fn strategies(
+        &self,
+        kerning: &Kerning,
+        model: &Acceleration,
+    ) -> Result<Vec<(Race, Strategy)>, Box<dyn Error>> {
+        Ok(self
+            .races(kerning)?
+            .into_iter()
+            .map(|race| Ok((race, race.strategy(model)?)))
+            .collect::<Result<_, ModelError>>()?)
+    }
+
+    fn total_ways(&self, kerning: &Kerning) -> Result<u128, Box<dyn Error>> {
+        Ok(self.races(kerning)?.iter().try_fold(1u128, |total, race| {
+            total.checked_mul(race.ways()).ok_or(OverflowError)
+        })?)
+    }
+}
+
+impl Solution for RaceSheet {
+    fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut times: Option<Row> = None;
+        let mut distances: Option<Row> = None;
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
+
+            if l.trim().is_empty() {
+                continue;
             }
-        } else if l.starts_with("Distance:") {
-            let mut parts = l.split(":");
-            parts.next();
-            let distances_parts = parts.next().unwrap_or_default().split_ascii_whitespace();
-
-            if join {
-                distances = vec![distances_parts
-                    .collect::<String>()
-                    .parse()
-                    .unwrap_or_default()];
-            } else {
-                distances = distances_parts
-                    .map(|number| number.parse().unwrap_or_default())
-                    .collect();
+
+            let (label, row) = Row::parse(l, index + 1)?;
+            let slot = match label.as_str() {
+                "Time" => &mut times,
+                "Distance" => &mut distances,
+                _ => {
+                    return Err(ParseError::invalid(
+                        "row label",
+                        &row.text,
+                        &row.text[..label.len()],
+                    )
+                    .at_line(row.line)
+                    .into())
+                }
+            };
+
+            if slot.is_some() {
+                return Err(ParseError::invalid(
+                    "repeated row",
+                    &row.text,
+                    &row.text[..label.len()],
+                )
+                .at_line(row.line)
+                .into());
             }
+
+            *slot = Some(row);
+        }
+
+        let (times, distances) = match (times, distances) {
+            (Some(times), Some(distances)) => (times, distances),
+            (None, Some(row)) => {
+                return Err(ParseError::missing("time row", &row.text)
+                    .at_line(row.line)
+                    .into())
+            }
+            (Some(row), None) => {
+                return Err(ParseError::missing("distance row", &row.text)
+                    .at_line(row.line)
+                    .into())
+            }
+            (None, None) => return Err(ParseError::missing("time row", "").into()),
+        };
+
+        if times.spans.len() != distances.spans.len() {
+            let text = &distances.text;
+            let span = distances
+                .spans
+                .get(times.spans.len())
+                .map_or(&text[text.len()..], |span| &text[span.clone()]);
+
+            return Err(ParseError::new(
+                ParseErrorKind::WrongCount {
+                    what: "distances",
+                    expected: times.spans.len(),
+                    found: distances.spans.len(),
+                },
+                text,
+                span,
+            )
+            .at_line(distances.line)
+            .into());
         }
+
+        for (time, distance) in times.spans.iter().zip(&distances.spans) {
+            if time.end != distance.end {
+                return Err(ParseError::invalid(
+                    "column alignment",
+                    &distances.text,
+                    &distances.text[distance.clone()],
+                )
+                .at_line(distances.line)
+                .into());
+            }
+        }
+
+        Ok(Self {
+            times: times.columns(),
+            distances: distances.columns(),
+        })
     }
 
-    let races = times
-        .iter()
-        .zip(distances.iter())
-        .map(|(&time, &distance)| Race::new(time, distance))
-        .collect::<Vec<_>>();
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        self.total_ways(&Kerning::Separate)
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        self.total_ways(&Kerning::All)
+    }
+}
 
-    Ok(races
-        .iter()
-        .map(|race| race.ways())
-        .fold(1, |acc, ways| acc * ways))
+pub fn total_ways(input: impl Read, kerning: &Kerning) -> Result<u128, Box<dyn Error>> {
+    RaceSheet::parse(input)?.total_ways(kerning)
+}
+
+pub fn explore(
+    input: impl Read,
+    kerning: &Kerning,
+    model: &Acceleration,
+) -> Result<Vec<(Race, Strategy)>, Box<dyn Error>> {
+    RaceSheet::parse(input)?.strategies(kerning, model)
 }
 
 #[cfg(test)]
@@ -80,14 +283,178 @@ Distance:  9  40  200";
 
     #[test]
     fn total_ways_without_join() -> Result<(), Box<dyn Error>> {
-        assert_eq!(288, total_ways(INPUT.as_bytes(), false)?);
+        assert_eq!(288, total_ways(INPUT.as_bytes(), &Kerning::Separate)?);
 
         Ok(())
     }
 
     #[test]
     fn total_ways_with_join() -> Result<(), Box<dyn Error>> {
-        assert_eq!(71_503, total_ways(INPUT.as_bytes(), true)?);
+        assert_eq!(71_503, total_ways(INPUT.as_bytes(), &Kerning::All)?);
+
+        Ok(())
+    }
+
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    fn brute_force(race: &Race) -> Option<RangeInclusive<u128>> {
+        let wins = (0..=race.time)
+            .filter(|&hold| hold * (race.time - hold) > race.distance)
+            .collect::<Vec<_>>();
+
+        Some(*wins.first()?..=*wins.last()?)
+    }
+
+    #[test]
+    fn winning_holds_match_brute_force() {
+        let mut random = XorShift(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..2_000 {
+            let time = u128::from(random.next() % 200);
+            let distance = u128::from(random.next()) % (time * time / 4 + 2);
+            let race = Race::new(time, distance);
+
+            assert_eq!(brute_force(&race), race.winning_holds(), "{race:?}");
+        }
+
+        for time in 0..60 {
+            for distance in 0..=time * time / 4 {
+                let race = Race::new(time, distance);
+
+                assert_eq!(brute_force(&race), race.winning_holds(), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn winning_holds_beyond_floating_point() {
+        let time = (1 << 60) + 1;
+        let race = Race::new(time, (time / 2) * (time - time / 2) - 1);
+
+        assert_eq!(Some(time / 2..=time - time / 2), race.winning_holds());
+        assert_eq!(2, race.ways());
+        assert_eq!(
+            None,
+            Race::new(time, (time / 2) * (time - time / 2)).winning_holds()
+        );
+
+        let race = Race::new(u128::MAX, u128::MAX);
+
+        assert_eq!(u128::MAX - 3, race.ways());
+    }
+
+    #[test]
+    fn total_ways_with_kerning() -> Result<(), Box<dyn Error>> {
+        let sheet = RaceSheet::parse(INPUT.as_bytes())?;
+
+        assert_eq!(
+            vec![Race::new(715, 940), Race::new(30, 200)],
+            sheet.races(&Kerning::Groups(2))?
+        );
+        assert_eq!(
+            vec![Race::new(730, 9200), Race::new(15, 40)],
+            sheet.races(&Kerning::Columns(vec![0, 2]))?
+        );
+        assert_eq!(
+            Err(KerningError::TooLong(String::from(
+                "340282366920938463463374607431768211456"
+            ))),
+            RaceSheet::parse(
+                "Time:     3402823669209384634633746 07431768211456\nDistance:                         1              2".as_bytes()
+            )?
+            .races(&Kerning::All)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_number() {
+        let input = "Time:      7  15   3O
+Distance:  9  40  200";
+
+        assert!(RaceSheet::parse(input.as_bytes()).is_err());
+        assert!(RaceSheet::parse_with(input.as_bytes(), ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn reject_mismatched_columns() {
+        assert_eq!(
+            Some(String::from(
+                "expected 3 distances, found 2 at line 2, column 17
+  Distance:  9  40
+                  ^"
+            )),
+            RaceSheet::parse("Time:      7  15   30\nDistance:  9  40".as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+        assert_eq!(
+            Some(String::from(
+                "invalid row label `Speed` at line 2, column 1
+  Speed:  9  40  200
+  ^^^^^"
+            )),
+            RaceSheet::parse("Time:      7  15   30\nSpeed:  9  40  200".as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+
+        let misaligned = "Time:      7  15   30
+Distance:  9  40 200";
+
+        assert_eq!(
+            Some(String::from(
+                "invalid column alignment `200` at line 2, column 18
+  Distance:  9  40 200
+                   ^^^"
+            )),
+            RaceSheet::parse(misaligned.as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+    }
+
+    #[test]
+    fn reject_missing_rows() {
+        assert_eq!(
+            Some(String::from(
+                "missing distance row at line 1, column 22
+  Time:      7  15   30
+                       ^"
+            )),
+            RaceSheet::parse("Time:      7  15   30\n\n".as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+        assert_eq!(
+            Some(String::from(
+                "missing time row at line 2, column 22
+  Distance:  9  40  200
+                       ^"
+            )),
+            RaceSheet::parse("\nDistance:  9  40  200".as_bytes())
+                .err()
+                .map(|e| e.to_string())
+        );
+        assert!(RaceSheet::parse("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn join_empty_rows() -> Result<(), Box<dyn Error>> {
+        let sheet = RaceSheet::parse("Time:\nDistance:".as_bytes())?;
+
+        assert_eq!(Vec::<Race>::new(), sheet.races(&Kerning::Separate)?);
+        assert_eq!(Err(KerningError::NoColumns), sheet.races(&Kerning::All));
 
         Ok(())
     }
@@ -6,12 +6,12 @@ use day06::*;
 fn main() -> Result<(), Box<dyn Error>> {
     println!(
         "Total number of ways without joining the numbers: {}",
-        total_ways(File::open("input.txt")?, false)?
+        total_ways(File::open("input.txt")?, &Kerning::Separate)?
     );
 
     println!(
         "Total number of ways with joining the numbers: {}",
-        total_ways(File::open("input.txt")?, true)?
+        total_ways(File::open("input.txt")?, &Kerning::All)?
     );
 
     Ok(())
@@ -0,0 +1,394 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::Race;
+
+const MAX_SEGMENTS: usize = 1 << 20;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Acceleration {
+    #[default]
+    Linear,
+    Capped {
+        top_speed: u128,
+    },
+    Decaying {
+        initial: u128,
+        divisor: u128,
+    },
+    Table(Vec<u128>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Strategy {
+    pub winning: Vec<RangeInclusive<u128>>,
+    pub best_hold: u128,
+    pub max_distance: u128,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ModelError {
+    ZeroDivisor,
+    TooManySegments,
+    Overflow,
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::ZeroDivisor => write!(f, "decaying acceleration needs a nonzero divisor"),
+            ModelError::TooManySegments => write!(
+                f,
+                "decaying acceleration changes rate more than {MAX_SEGMENTS} times"
+            ),
+            ModelError::Overflow => write!(f, "result does not fit in 128 bits"),
+        }
+    }
+}
+
+impl Error for ModelError {}
+
+struct Segment {
+    start: u128,
+    speed: Option<u128>,
+    gain: u128,
+    decrement: u128,
+    steps: u128,
+}
+
+fn series(gain: u128, decrement: u128, steps: u128) -> Option<u128> {
+    let last = gain - (steps - 1) * decrement;
+    let triangle = if steps.is_multiple_of(2) {
+        (steps / 2).checked_mul(steps - 1)?
+    } else {
+        steps.checked_mul((steps - 1) / 2)?
+    };
+
+    steps
+        .checked_mul(last)?
+        .checked_add(decrement.checked_mul(triangle)?)
+}
+
+fn decay(initial: u128, divisor: u128, time: u128) -> Result<Vec<Segment>, ModelError> {
+    let mut segments = vec![];
+    let (mut start, mut speed, mut gain) = (0, Some(0u128), initial);
+
+    while gain > 0 && start < time {
+        if segments.len() == MAX_SEGMENTS {
+            return Err(ModelError::TooManySegments);
+        }
+
+        let decrement = gain.div_ceil(divisor);
+        let steps = (gain - (decrement - 1) * divisor)
+            .div_ceil(decrement)
+            .min(time - start);
+
+        segments.push(Segment {
+            start,
+            speed,
+            gain,
+            decrement,
+            steps,
+        });
+
+        speed = speed
+            .zip(series(gain, decrement, steps))
+            .and_then(|(a, b)| a.checked_add(b));
+        start += steps;
+        gain -= steps * decrement;
+    }
+
+    Ok(segments)
+}
+
+fn decayed_speed(segments: &[Segment], hold: u128) -> Option<u128> {
+    let Some(segment) = segments[..segments.partition_point(|segment| segment.start < hold)].last()
+    else {
+        return Some(0);
+    };
+
+    let steps = (hold - segment.start).min(segment.steps);
+
+    segment
+        .speed?
+        .checked_add(series(segment.gain, segment.decrement, steps)?)
+}
+
+impl Acceleration {
+    fn gain(&self, step: u128, previous: u128) -> u128 {
+        match self {
+            Acceleration::Linear => 1,
+            Acceleration::Capped { top_speed } => u128::from(step < *top_speed),
+            Acceleration::Decaying { initial, divisor } => {
+                if step == 0 {
+                    *initial
+                } else {
+                    previous - previous.div_ceil(*divisor)
+                }
+            }
+            Acceleration::Table(gains) => usize::try_from(step)
+                .ok()
+                .and_then(|step| gains.get(step))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Strategy {
+    pub fn ways(&self) -> u128 {
+        self.winning
+            .iter()
+            .map(|holds| holds.end() - holds.start() + 1)
+            .sum()
+    }
+}
+
+fn last_true(mut low: u128, mut high: u128, predicate: impl Fn(u128) -> bool) -> u128 {
+    while low < high {
+        let middle = low + (high - low).div_ceil(2);
+
+        if predicate(middle) {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+
+    low
+}
+
+impl Race {
+    pub fn strategy(&self, model: &Acceleration) -> Result<Strategy, ModelError> {
+        match model {
+            Acceleration::Linear => self.search(self.time / 2, Some),
+            Acceleration::Capped { top_speed } => self
+                .search((self.time / 2).min(*top_speed), |hold| {
+                    Some(hold.min(*top_speed))
+                }),
+            Acceleration::Decaying { divisor: 0, .. } => Err(ModelError::ZeroDivisor),
+            Acceleration::Decaying { initial, divisor } => {
+                let segments = decay(*initial, *divisor, self.time)?;
+                let speed = |hold| decayed_speed(&segments, hold);
+                let distance = |hold: u128| {
+                    speed(hold)
+                        .and_then(|speed| speed.checked_mul(self.time - hold))
+                        .unwrap_or(u128::MAX)
+                };
+                let peak = last_true(0, self.time, |hold| distance(hold) > distance(hold - 1));
+
+                self.search(peak, speed)
+            }
+            Acceleration::Table(gains) => self.scan(model, gains.len() as u128),
+        }
+    }
+
+    fn search(
+        &self,
+        peak: u128,
+        speed: impl Fn(u128) -> Option<u128>,
+    ) -> Result<Strategy, ModelError> {
+        let distance = |hold| speed(hold)?.checked_mul(self.time - hold);
+        let wins = |hold| distance(hold).is_none_or(|distance| distance > self.distance);
+        let max_distance = distance(peak).ok_or(ModelError::Overflow)?;
+
+        let winning = if wins(peak) {
+            let first = last_true(0, peak, |hold| !wins(hold)) + 1;
+            let last = last_true(peak, self.time, wins);
+
+            vec![first..=last]
+        } else {
+            vec![]
+        };
+
+        Ok(Strategy {
+            winning,
+            best_hold: peak,
+            max_distance,
+        })
+    }
+
+    fn scan(&self, model: &Acceleration, limit: u128) -> Result<Strategy, ModelError> {
+        let mut winning: Vec<RangeInclusive<u128>> = vec![];
+        let (mut best_hold, mut max_distance) = (0, 0);
+        let (mut speed, mut gain) = (0u128, 0);
+
+        for hold in 0..=self.time.min(limit) {
+            if hold > 0 {
+                gain = model.gain(hold - 1, gain);
+                speed = speed.checked_add(gain).ok_or(ModelError::Overflow)?;
+            }
+
+            let distance = speed
+                .checked_mul(self.time - hold)
+                .ok_or(ModelError::Overflow)?;
+
+            if distance > max_distance {
+                (best_hold, max_distance) = (hold, distance);
+            }
+
+            if distance > self.distance {
+                match winning.last_mut() {
+                    Some(holds) if *holds.end() + 1 == hold => *holds = *holds.start()..=hold,
+                    _ => winning.push(hold..=hold),
+                }
+            }
+        }
+
+        let wins = |hold| {
+            speed
+                .checked_mul(self.time - hold)
+                .is_none_or(|distance| distance > self.distance)
+        };
+
+        if limit < self.time && wins(limit + 1) {
+            let last = last_true(limit + 1, self.time, wins);
+
+            match winning.last_mut() {
+                Some(holds) if *holds.end() == limit => *holds = *holds.start()..=last,
+                _ => winning.push(limit + 1..=last),
+            }
+        }
+
+        Ok(Strategy {
+            winning,
+            best_hold,
+            max_distance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn closed_forms_match_scan() -> Result<(), Box<dyn Error>> {
+        for time in 0..40 {
+            for distance in 0..=time * time / 4 {
+                let race = Race::new(time, distance);
+
+                for model in (0..=time / 2 + 1)
+                    .map(|top_speed| Acceleration::Capped { top_speed })
+                    .chain((0..6).flat_map(|initial| {
+                        (1..5).map(move |divisor| Acceleration::Decaying { initial, divisor })
+                    }))
+                    .chain([
+                        Acceleration::Linear,
+                        Acceleration::Table(vec![5, 0, 0, 10]),
+                        Acceleration::Table(vec![0, 3, 1, 0, 2, 2, 1]),
+                    ])
+                {
+                    assert_eq!(
+                        race.scan(&model, time)?,
+                        race.strategy(&model)?,
+                        "{race:?} {model:?}"
+                    );
+                }
+
+                assert_eq!(
+                    race.winning_holds().into_iter().collect::<Vec<_>>(),
+                    race.strategy(&Acceleration::Linear)?.winning
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn explore_models() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            Strategy {
+                winning: vec![1..=4],
+                best_hold: 2,
+                max_distance: 10,
+            },
+            Race::new(7, 5).strategy(&Acceleration::Capped { top_speed: 2 })?
+        );
+        assert_eq!(
+            Strategy {
+                winning: vec![1..=2],
+                best_hold: 2,
+                max_distance: 18,
+            },
+            Race::new(5, 15).strategy(&Acceleration::Decaying {
+                initial: 4,
+                divisor: 2,
+            })?
+        );
+
+        assert_eq!(
+            Err(ModelError::ZeroDivisor),
+            Race::new(5, 15).strategy(&Acceleration::Decaying {
+                initial: 4,
+                divisor: 0,
+            })
+        );
+
+        let strategy = Race::new(6, 20).strategy(&Acceleration::Table(vec![5, 0, 0, 10]))?;
+
+        assert_eq!(vec![1..=1, 4..=4], strategy.winning);
+        assert_eq!(
+            (4, 30, 2),
+            (strategy.best_hold, strategy.max_distance, strategy.ways())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn decay_over_long_races() -> Result<(), Box<dyn Error>> {
+        let (mut speed, mut steps, mut gain) = (0, 0, 1000u128);
+
+        while gain > 0 {
+            (speed, steps, gain) = (speed + gain, steps + 1, gain - gain.div_ceil(10));
+        }
+
+        let time = 1 << 100;
+        let strategy =
+            Race::new(time, speed * (time - steps) - 1).strategy(&Acceleration::Decaying {
+                initial: 1000,
+                divisor: 10,
+            })?;
+
+        assert_eq!(
+            Strategy {
+                winning: vec![steps..=steps],
+                best_hold: steps,
+                max_distance: speed * (time - steps),
+            },
+            strategy
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_over_long_races() -> Result<(), Box<dyn Error>> {
+        let time = 1 << 64;
+        let strategy = Race::new(time, 15_000).strategy(&Acceleration::Table(vec![5, 0, 0, 10]))?;
+
+        assert_eq!(
+            Strategy {
+                winning: vec![1..=time - 1001],
+                best_hold: 4,
+                max_distance: 15 * (time - 4),
+            },
+            strategy
+        );
+
+        assert_eq!(
+            Err(ModelError::TooManySegments),
+            Race::new(time, 0).strategy(&Acceleration::Decaying {
+                initial: 1 << 100,
+                divisor: 1 << 20,
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt::Write;
+
+use crate::{CamelCards, Card, HandType, Ruleset};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandAnalysis {
+    pub line: usize,
+    pub cards: [Card; 5],
+    pub bid: u64,
+    pub hand_type: HandType,
+    pub substitutions: Vec<(usize, Card)>,
+    pub rank: usize,
+    pub winnings: u64,
+}
+
+impl HandAnalysis {
+    pub fn resolved(&self) -> [Card; 5] {
+        let mut resolved = self.cards;
+
+        for &(index, card) in &self.substitutions {
+            resolved[index] = card;
+        }
+
+        resolved
+    }
+}
+
+impl CamelCards {
+    pub fn analyze(&self, ruleset: &Ruleset) -> Vec<HandAnalysis> {
+        let mut hands = self
+            .hands
+            .iter()
+            .enumerate()
+            .map(|(index, hand)| (ruleset.key(&hand.cards), index, hand))
+            .collect::<Vec<_>>();
+
+        hands.sort_by(|a, b| a.0.cmp(&b.0));
+
+        hands
+            .into_iter()
+            .enumerate()
+            .map(|(position, ((hand_type, _), index, hand))| {
+                let resolved = ruleset.resolve(&hand.cards);
+
+                HandAnalysis {
+                    line: index + 1,
+                    cards: hand.cards,
+                    bid: hand.bid,
+                    hand_type,
+                    substitutions: (0..5)
+                        .filter(|&index| ruleset.wild.contains(&hand.cards[index]))
+                        .map(|index| (index, resolved[index]))
+                        .collect(),
+                    rank: position + 1,
+                    winnings: (position as u64 + 1) * hand.bid,
+                }
+            })
+            .collect()
+    }
+}
+
+fn glyphs(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect()
+}
+
+pub fn render_table(analyses: &[HandAnalysis]) -> String {
+    let header = ["rank", "line", "hand", "type", "wild", "bid", "winnings"].map(String::from);
+    let rows = analyses
+        .iter()
+        .map(|analysis| {
+            let wild = if analysis.substitutions.is_empty() {
+                String::from("-")
+            } else {
+                analysis
+                    .substitutions
+                    .iter()
+                    .map(|&(index, card)| format!("{}={card}", analysis.cards[index]))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+
+            [
+                analysis.rank.to_string(),
+                analysis.line.to_string(),
+                glyphs(&analysis.cards),
+                analysis.hand_type.to_string(),
+                wild,
+                analysis.bid.to_string(),
+                analysis.winnings.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header.clone().map(|cell| cell.len());
+
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+
+    for row in [header].iter().chain(&rows) {
+        let cells = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 | 1 | 5 | 6 => format!("{cell:>width$}"),
+                _ => format!("{cell:<width$}"),
+            })
+            .collect::<Vec<_>>();
+
+        let _ = writeln!(table, "{}", cells.join("  ").trim_end());
+    }
+
+    let _ = writeln!(
+        table,
+        "total winnings: {}",
+        analyses
+            .iter()
+            .map(|analysis| analysis.winnings)
+            .sum::<u64>()
+    );
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use common::Solution;
+
+    use super::*;
+
+    const INPUT: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn analyze_hands_with_jokers() -> Result<(), Box<dyn Error>> {
+        let analyses = CamelCards::parse(INPUT.as_bytes())?.analyze(&Ruleset::jokers());
+        let strongest = &analyses[4];
+
+        assert_eq!(
+            vec![1, 3, 2, 5, 4],
+            analyses
+                .iter()
+                .map(|analysis| analysis.line)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(HandType::FourOfAKind, strongest.hand_type);
+        assert_eq!(
+            vec![(2, Card::Ten), (3, Card::Ten)],
+            strongest.substitutions
+        );
+        assert_eq!((5, 1100), (strongest.rank, strongest.winnings));
+        assert_eq!("KTTTT", glyphs(&strongest.resolved()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn keep_wild_cards_resolved_to_themselves() -> Result<(), Box<dyn Error>> {
+        let ruleset = Ruleset {
+            wild: vec![Card::Jack],
+            ..Ruleset::poker()
+        };
+        let analyses = CamelCards::parse("9TJQK 10".as_bytes())?.analyze(&ruleset);
+
+        assert_eq!(HandType::Straight, analyses[0].hand_type);
+        assert_eq!(vec![(2, Card::Jack)], analyses[0].substitutions);
+        assert!(render_table(&analyses).contains("J=J"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_audit_table() -> Result<(), Box<dyn Error>> {
+        let analyses = CamelCards::parse(INPUT.as_bytes())?.analyze(&Ruleset::jokers());
+
+        assert_eq!(
+            "rank  line  hand   type            wild     bid  winnings
+   1     1  32T3K  one pair        -        765       765
+   2     3  KK677  two pair        -         28        56
+   3     2  T55J5  four of a kind  J=5      684      2052
+   4     5  QQQJA  four of a kind  J=Q      483      1932
+   5     4  KTJJT  four of a kind  J=T J=T  220      1100
+total winnings: 5905
+",
+            render_table(&analyses)
+        );
+
+        Ok(())
+    }
+}
@@ -1,12 +1,21 @@
-use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::error::Error;
-use std::fmt;
+use std::fmt::{self, Display};
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Card {
+use common::{
+    next_token, parse_glyphs, parse_number, FromGlyph, ParseError, ParseErrorKind, ParseMode,
+    Solution,
+};
+
+mod analysis;
+mod ruleset;
+
+pub use analysis::{render_table, HandAnalysis};
+pub use ruleset::{Classification, Ruleset, TieBreak, UnknownRulesetError, DECK};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Card {
     Joker,
     Two,
     Three,
@@ -23,165 +32,165 @@ enum Card {
     Ace,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
     ThreeOfAKind,
+    Straight,
     FullHouse,
     FourOfAKind,
     FiveOfAKind,
 }
 
-#[derive(Eq)]
+#[derive(Clone)]
 struct Hand {
     cards: [Card; 5],
     bid: u64,
 }
 
-#[derive(Debug)]
-struct ParseHandError;
+pub struct CamelCards {
+    hands: Vec<Hand>,
+}
+
+impl FromGlyph for Card {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '2' => Some(Card::Two),
+            '3' => Some(Card::Three),
+            '4' => Some(Card::Four),
+            '5' => Some(Card::Five),
+            '6' => Some(Card::Six),
+            '7' => Some(Card::Seven),
+            '8' => Some(Card::Eight),
+            '9' => Some(Card::Nine),
+            'T' => Some(Card::Ten),
+            'J' => Some(Card::Jack),
+            'Q' => Some(Card::Queen),
+            'K' => Some(Card::King),
+            'A' => Some(Card::Ace),
+            _ => None,
+        }
+    }
+}
 
-impl fmt::Display for ParseHandError {
+impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse hand")
+        let glyph = match self {
+            Card::Joker => '*',
+            Card::Two => '2',
+            Card::Three => '3',
+            Card::Four => '4',
+            Card::Five => '5',
+            Card::Six => '6',
+            Card::Seven => '7',
+            Card::Eight => '8',
+            Card::Nine => '9',
+            Card::Ten => 'T',
+            Card::Jack => 'J',
+            Card::Queen => 'Q',
+            Card::King => 'K',
+            Card::Ace => 'A',
+        };
+
+        write!(f, "{glyph}")
     }
 }
 
-impl Error for ParseHandError {}
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandType::HighCard => write!(f, "high card"),
+            HandType::OnePair => write!(f, "one pair"),
+            HandType::TwoPair => write!(f, "two pair"),
+            HandType::ThreeOfAKind => write!(f, "three of a kind"),
+            HandType::Straight => write!(f, "straight"),
+            HandType::FullHouse => write!(f, "full house"),
+            HandType::FourOfAKind => write!(f, "four of a kind"),
+            HandType::FiveOfAKind => write!(f, "five of a kind"),
+        }
+    }
+}
 
 impl From<char> for Card {
     fn from(value: char) -> Self {
-        match value {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'J' => Card::Jack,
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            'A' => Card::Ace,
-            _ => Card::Joker,
-        }
+        Card::from_glyph(value).unwrap_or(Card::Joker)
     }
 }
 
 impl FromStr for Hand {
-    type Err = ParseHandError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split_ascii_whitespace();
-
-        let cards = parts
-            .next()
-            .ok_or(ParseHandError)?
-            .chars()
-            .map(|c| c.into())
-            .collect::<Vec<_>>()
-            .try_into()
-            .map_err(|_| ParseHandError)?;
-
-        let bid = parts
-            .next()
-            .ok_or(ParseHandError)?
-            .parse()
-            .map_err(|_| ParseHandError)?;
-
-        Ok(Self { cards, bid })
+        Self::parse_with(s, ParseMode::Lenient)
     }
 }
 
 impl Hand {
-    fn hand_type(&self) -> HandType {
-        let mut tally = HashMap::new();
-        let mut joker_count = 0;
-
-        for card in self.cards {
-            if card == Card::Joker {
-                joker_count += 1;
-            } else {
-                *tally.entry(card).or_insert(0) += 1;
-            }
-        }
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
+        let mut parts = s.split_ascii_whitespace();
 
-        let mut card_counts = tally.values().cloned().collect::<Vec<_>>();
-        card_counts.sort();
-
-        let max_count = card_counts.pop().unwrap_or_default();
-        let max_2_count = card_counts.pop().unwrap_or_default();
-
-        if max_count + joker_count == 5 {
-            HandType::FiveOfAKind
-        } else if max_count + joker_count == 4 {
-            HandType::FourOfAKind
-        } else if max_count + joker_count == 3 && max_2_count == 2 {
-            HandType::FullHouse
-        } else if max_count + joker_count == 3 {
-            HandType::ThreeOfAKind
-        } else if max_count == 2 && max_2_count + joker_count == 2 {
-            HandType::TwoPair
-        } else if max_count + joker_count == 2 {
-            HandType::OnePair
-        } else {
-            HandType::HighCard
-        }
-    }
-}
+        let label = next_token(&mut parts, "cards", s)?;
+        let cards = parse_glyphs(label, mode)
+            .map_err(|e| e.within(s, label))?
+            .try_into()
+            .map_err(|cards: Vec<Card>| {
+                ParseError::new(
+                    ParseErrorKind::WrongCount {
+                        what: "cards",
+                        expected: 5,
+                        found: cards.len(),
+                    },
+                    s,
+                    label,
+                )
+            })?;
+
+        let bid = parse_number(next_token(&mut parts, "bid", s)?, s)?;
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.cards == other.cards
+        Ok(Self { cards, bid })
     }
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl CamelCards {
+    fn total_winnings(&self, ruleset: &Ruleset) -> u64 {
+        self.analyze(ruleset)
+            .iter()
+            .map(|analysis| analysis.winnings)
+            .sum()
     }
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.hand_type().cmp(&other.hand_type()).then_with(|| {
-            for (card, other_card) in self.cards.iter().zip(other.cards.iter()) {
-                let card_ordering = card.cmp(other_card);
+impl Solution for CamelCards {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut hands = vec![];
 
-                if card_ordering.is_ne() {
-                    return card_ordering;
-                }
-            }
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            hands.push(Hand::parse_with(&line?, mode).map_err(|e| e.at_line(index + 1))?);
+        }
 
-            Ordering::Equal
-        })
+        Ok(Self { hands })
     }
-}
-
-pub fn total_winnings(input: impl Read, jokers: bool) -> Result<u64, Box<dyn Error>> {
-    let mut hands = vec![];
-
-    for line in io::BufReader::new(input).lines() {
-        let mut l = line?;
 
-        if jokers {
-            l = l.replace("J", "*");
-        }
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.total_winnings(&Ruleset::standard()))
+    }
 
-        hands.push(l.parse::<Hand>()?);
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.total_winnings(&Ruleset::jokers()))
     }
+}
 
-    hands.sort();
+pub fn total_winnings(input: impl Read, ruleset: &Ruleset) -> Result<u64, Box<dyn Error>> {
+    Ok(CamelCards::parse(input)?.total_winnings(ruleset))
+}
 
-    Ok(hands
-        .iter()
-        .enumerate()
-        .map(|(index, hand)| (index as u64 + 1) * hand.bid)
-        .sum())
+pub fn analyze_hands(
+    input: impl Read,
+    ruleset: &Ruleset,
+) -> Result<Vec<HandAnalysis>, Box<dyn Error>> {
+    Ok(CamelCards::parse(input)?.analyze(ruleset))
 }
 
 #[cfg(test)]
@@ -196,15 +205,53 @@ QQQJA 483";
 
     #[test]
     fn total_winnings_without_jokers() -> Result<(), Box<dyn Error>> {
-        assert_eq!(6440, total_winnings(INPUT.as_bytes(), false)?);
+        assert_eq!(
+            6440,
+            total_winnings(INPUT.as_bytes(), &Ruleset::standard())?
+        );
 
         Ok(())
     }
 
     #[test]
     fn total_winnings_with_jokers() -> Result<(), Box<dyn Error>> {
-        assert_eq!(5905, total_winnings(INPUT.as_bytes(), true)?);
+        assert_eq!(5905, total_winnings(INPUT.as_bytes(), &Ruleset::jokers())?);
 
         Ok(())
     }
+
+    #[test]
+    fn report_wrong_card_count() {
+        let input = "32T3K 765
+T55J 684";
+
+        let error = total_winnings(input.as_bytes(), &Ruleset::standard())
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(
+            "expected 5 cards, found 4 `T55J` at line 2, column 1
+  T55J 684
+  ^^^^",
+            error
+        );
+    }
+
+    #[test]
+    fn reject_unknown_card_when_strict() {
+        let input = "32T3K 765
+T55X5 684";
+
+        assert!(CamelCards::parse(input.as_bytes()).is_ok());
+        assert_eq!(
+            Some(String::from(
+                "invalid character `X` at line 2, column 4
+  T55X5 684
+     ^"
+            )),
+            CamelCards::parse_with(input.as_bytes(), ParseMode::Strict)
+                .err()
+                .map(|e| e.to_string())
+        );
+    }
 }
@@ -1,17 +1,50 @@
+use std::env;
 use std::error::Error;
 use std::fs::File;
 
 use day07::*;
 
+const USAGE: &str = "Usage:
+    day07
+    day07 table [--rules <standard|jokers|poker>] [--input <path>]";
+
+fn print_table(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut ruleset = Ruleset::standard();
+    let mut input = String::from("input.txt");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => ruleset = args.next().ok_or("missing value for rules")?.parse()?,
+            "--input" => input = args.next().ok_or("missing value for input")?,
+            _ => return Err(format!("unexpected argument `{arg}`\n\n{USAGE}").into()),
+        }
+    }
+
+    print!(
+        "{}",
+        render_table(&analyze_hands(File::open(input)?, &ruleset)?)
+    );
+
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("table") => return print_table(args),
+        Some(other) => return Err(format!("unknown command `{other}`\n\n{USAGE}").into()),
+        None => {}
+    }
+
     println!(
         "Total winnings without jokers: {}",
-        total_winnings(File::open("input.txt")?, false)?
+        total_winnings(File::open("input.txt")?, &Ruleset::standard())?
     );
 
     println!(
         "Total winnings with jokers: {}",
-        total_winnings(File::open("input.txt")?, true)?
+        total_winnings(File::open("input.txt")?, &Ruleset::jokers())?
     );
 
     Ok(())
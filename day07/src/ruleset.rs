@@ -0,0 +1,344 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Card, HandType};
+
+pub const DECK: [Card; 13] = [
+    Card::Two,
+    Card::Three,
+    Card::Four,
+    Card::Five,
+    Card::Six,
+    Card::Seven,
+    Card::Eight,
+    Card::Nine,
+    Card::Ten,
+    Card::Jack,
+    Card::Queen,
+    Card::King,
+    Card::Ace,
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Classification {
+    #[default]
+    Camel,
+    Poker,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    #[default]
+    Positional,
+    Grouped,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ruleset {
+    pub ranks: Vec<Card>,
+    pub wild: Vec<Card>,
+    pub classification: Classification,
+    pub tie_break: TieBreak,
+}
+
+#[derive(Debug)]
+pub struct UnknownRulesetError(String);
+
+impl fmt::Display for UnknownRulesetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown ruleset `{}`, expected standard, jokers or poker",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownRulesetError {}
+
+impl FromStr for Ruleset {
+    type Err = UnknownRulesetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Ruleset::standard()),
+            "jokers" => Ok(Ruleset::jokers()),
+            "poker" => Ok(Ruleset::poker()),
+            _ => Err(UnknownRulesetError(s.to_string())),
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+fn straight_top(cards: &[Card; 5]) -> Option<Card> {
+    let mut sorted = *cards;
+    sorted.sort();
+
+    if sorted == [Card::Two, Card::Three, Card::Four, Card::Five, Card::Ace] {
+        return Some(Card::Five);
+    }
+
+    let consecutive = sorted[0] != Card::Joker
+        && sorted
+            .windows(2)
+            .all(|pair| pair[1] as usize == pair[0] as usize + 1);
+
+    consecutive.then_some(sorted[4])
+}
+
+impl Ruleset {
+    pub fn standard() -> Self {
+        Self {
+            ranks: [Card::Joker].into_iter().chain(DECK).collect(),
+            wild: vec![Card::Joker],
+            classification: Classification::Camel,
+            tie_break: TieBreak::Positional,
+        }
+    }
+
+    pub fn jokers() -> Self {
+        Self {
+            ranks: [Card::Joker, Card::Jack]
+                .into_iter()
+                .chain(DECK.into_iter().filter(|&card| card != Card::Jack))
+                .collect(),
+            wild: vec![Card::Joker, Card::Jack],
+            classification: Classification::Camel,
+            tie_break: TieBreak::Positional,
+        }
+    }
+
+    pub fn poker() -> Self {
+        Self {
+            ranks: [Card::Joker].into_iter().chain(DECK).collect(),
+            wild: vec![Card::Joker],
+            classification: Classification::Poker,
+            tie_break: TieBreak::Grouped,
+        }
+    }
+
+    pub fn rank(&self, card: Card) -> usize {
+        self.ranks
+            .iter()
+            .position(|&ranked| ranked == card)
+            .map_or(0, |position| position + 1)
+    }
+
+    fn hand_type(&self, cards: &[Card; 5]) -> HandType {
+        let mut tally = HashMap::new();
+
+        for &card in cards {
+            *tally.entry(card).or_insert(0) += 1;
+        }
+
+        let mut counts = tally.into_values().collect::<Vec<_>>();
+        counts.sort_by_key(|&count| Reverse(count));
+
+        match counts.as_slice() {
+            [5] => HandType::FiveOfAKind,
+            [4, ..] => HandType::FourOfAKind,
+            [3, 2] => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ if self.classification == Classification::Poker && straight_top(cards).is_some() => {
+                HandType::Straight
+            }
+            _ => HandType::HighCard,
+        }
+    }
+
+    fn grouped(&self, cards: &[Card; 5], hand_type: HandType) -> Vec<usize> {
+        if let Some(top) = straight_top(cards).filter(|_| hand_type == HandType::Straight) {
+            return vec![self.rank(top)];
+        }
+
+        let mut tally = HashMap::new();
+
+        for &card in cards {
+            *tally.entry(self.rank(card)).or_insert(0) += 1;
+        }
+
+        let mut groups = tally.into_iter().collect::<Vec<_>>();
+        groups.sort_by_key(|&(rank, count)| Reverse((count, rank)));
+
+        groups
+            .into_iter()
+            .flat_map(|(rank, count)| std::iter::repeat_n(rank, count))
+            .collect()
+    }
+
+    fn strength(&self, cards: &[Card; 5]) -> (HandType, Vec<usize>) {
+        let hand_type = self.hand_type(cards);
+
+        (hand_type, self.grouped(cards, hand_type))
+    }
+
+    pub fn resolve(&self, cards: &[Card; 5]) -> [Card; 5] {
+        let wild = (0..5)
+            .filter(|&index| self.wild.contains(&cards[index]))
+            .collect::<Vec<_>>();
+
+        if wild.is_empty() {
+            return *cards;
+        }
+
+        let substitute = |substitutes: &mut dyn Iterator<Item = Card>| {
+            let mut resolved = *cards;
+
+            for (&index, card) in wild.iter().zip(substitutes) {
+                resolved[index] = card;
+            }
+
+            resolved
+        };
+
+        let mut candidates = self
+            .ranks
+            .iter()
+            .filter(|card| !self.wild.contains(card))
+            .map(|&card| substitute(&mut std::iter::repeat(card)))
+            .collect::<Vec<_>>();
+
+        let fixed = (0..5)
+            .filter(|index| !wild.contains(index))
+            .map(|index| cards[index])
+            .collect::<Vec<_>>();
+        let distinct = fixed
+            .iter()
+            .enumerate()
+            .all(|(index, card)| !fixed[..index].contains(card));
+
+        if self.classification == Classification::Poker && distinct {
+            let naturals = [Card::Ace].into_iter().chain(DECK).collect::<Vec<_>>();
+
+            for window in naturals.windows(5) {
+                if fixed.iter().all(|card| window.contains(card)) {
+                    candidates.push(substitute(
+                        &mut window.iter().copied().filter(|card| !fixed.contains(card)),
+                    ));
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .max_by_key(|resolved| self.strength(resolved))
+            .unwrap_or(*cards)
+    }
+
+    pub fn classify(&self, cards: &[Card; 5]) -> HandType {
+        self.hand_type(&self.resolve(cards))
+    }
+
+    pub fn key(&self, cards: &[Card; 5]) -> (HandType, Vec<usize>) {
+        let (hand_type, grouped) = self.strength(&self.resolve(cards));
+
+        match self.tie_break {
+            TieBreak::Positional => (
+                hand_type,
+                cards.iter().map(|&card| self.rank(card)).collect(),
+            ),
+            TieBreak::Grouped => (hand_type, grouped),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::parse_glyphs;
+
+    use super::*;
+
+    fn cards(hand: &str) -> Result<[Card; 5], Box<dyn Error>> {
+        parse_glyphs::<Card>(hand, common::ParseMode::Strict)?
+            .try_into()
+            .map_err(|_| "expected 5 cards".into())
+    }
+
+    #[test]
+    fn classify_with_presets() -> Result<(), Box<dyn Error>> {
+        let jokers = Ruleset::jokers();
+
+        assert_eq!(cards("KTTTT")?, jokers.resolve(&cards("KTJJT")?));
+        assert_eq!(HandType::FiveOfAKind, jokers.classify(&cards("JJJJJ")?));
+        assert_eq!(
+            HandType::TwoPair,
+            Ruleset::standard().classify(&cards("KTJJT")?)
+        );
+        assert_eq!(
+            HandType::HighCard,
+            Ruleset::standard().classify(&cards("23456")?)
+        );
+
+        let poker = "poker".parse::<Ruleset>()?;
+
+        for hand in ["23456", "A2345", "TJQKA", "5A432"] {
+            assert_eq!(HandType::Straight, poker.classify(&cards(hand)?), "{hand}");
+        }
+
+        assert_eq!(HandType::HighCard, poker.classify(&cards("QKA23")?));
+        assert!("holdem".parse::<Ruleset>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn treat_unknown_cards_as_jokers() -> Result<(), Box<dyn Error>> {
+        let unknown = |hand: &str| -> Result<[Card; 5], Box<dyn Error>> {
+            parse_glyphs::<Card>(hand, common::ParseMode::Lenient)?
+                .try_into()
+                .map_err(|_| "expected 5 cards".into())
+        };
+
+        for (ruleset, hand_type) in [
+            (Ruleset::standard(), HandType::OnePair),
+            (Ruleset::jokers(), HandType::OnePair),
+            (Ruleset::poker(), HandType::Straight),
+        ] {
+            assert!(ruleset.rank(Card::Joker) < ruleset.rank(Card::Two));
+            assert_eq!(hand_type, ruleset.classify(&unknown("2?345")?));
+        }
+
+        assert_eq!(
+            HandType::FourOfAKind,
+            Ruleset::jokers().classify(&unknown("J?J23")?)
+        );
+        assert!(
+            Ruleset::standard().key(&unknown("?2345")?) < Ruleset::standard().key(&cards("22345")?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn break_ties_by_groups() -> Result<(), Box<dyn Error>> {
+        let poker = Ruleset::poker();
+        let key = |hand| Ok::<_, Box<dyn Error>>(poker.key(&cards(hand)?));
+
+        assert!(key("KK234")? < key("2KK35")?);
+        assert!(key("44333")? < key("33444")?);
+        assert!(key("A2345")? < key("65432")?);
+        assert!(key("AAKQJ")? < key("22234")?);
+        assert_eq!(key("K2K34")?, key("34KK2")?);
+
+        let wild_poker = Ruleset {
+            wild: vec![Card::Jack],
+            ..Ruleset::poker()
+        };
+
+        assert_eq!(cards("23456")?, wild_poker.resolve(&cards("23J56")?));
+        assert_eq!(cards("234A5")?, wild_poker.resolve(&cards("2J4A5")?));
+        assert_eq!(cards("KKQT8")?, wild_poker.resolve(&cards("JKQT8")?));
+
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::fmt;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
+use common::{next_token, parse_glyphs, FromGlyph, ParseError, ParseMode, Solution};
+
 #[derive(Clone, Copy)]
 enum Direction {
     Left,
@@ -16,47 +18,39 @@ struct Node {
     right: String,
 }
 
-struct Map {
+pub struct Map {
     directions: Vec<Direction>,
     nodes: HashMap<String, Node>,
 }
 
-#[derive(Debug)]
-struct ParseMapError;
-
-impl fmt::Display for ParseMapError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse map")
+impl FromGlyph for Direction {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
     }
 }
 
-impl Error for ParseMapError {}
-
 impl From<char> for Direction {
     fn from(value: char) -> Self {
-        match value {
-            'L' => Direction::Left,
-            _ => Direction::Right,
-        }
+        Direction::from_glyph(value).unwrap_or(Direction::Right)
     }
 }
 
 impl FromStr for Node {
-    type Err = ParseMapError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split(" = ");
-        let name = parts.next().ok_or(ParseMapError)?.to_string();
+        let name = next_token(&mut parts, "node name", s)?.to_string();
 
-        let mut direction_parts = parts.next().ok_or(ParseMapError)?.split(", ");
-        let left = direction_parts
-            .next()
-            .ok_or(ParseMapError)?
+        let mut direction_parts = next_token(&mut parts, "node targets", s)?.split(", ");
+        let left = next_token(&mut direction_parts, "left target", s)?
             .trim_start_matches("(")
             .to_string();
-        let right = direction_parts
-            .next()
-            .ok_or(ParseMapError)?
+        let right = next_token(&mut direction_parts, "right target", s)?
             .trim_end_matches(")")
             .to_string();
 
@@ -129,7 +123,7 @@ impl Map {
             .keys()
             .filter(|name| name.ends_with("A"))
             .map(|name| self.steps_to_any(name.to_string()))
-            .fold(1, |acc, steps| lcm(acc, steps))
+            .fold(1, lcm)
     }
 
     fn steps_to_any(&self, start: String) -> u64 {
@@ -148,23 +142,39 @@ impl Map {
     }
 }
 
-pub fn total_steps(input: impl Read, multiple: bool) -> Result<u64, Box<dyn Error>> {
-    let mut map = Map::new();
+impl Solution for Map {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut map = Map::new();
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
 
-        if l.is_empty() {
-            continue;
-        }
+            if l.is_empty() {
+                continue;
+            }
 
-        if map.is_empty() {
-            map.add_directions(l.chars().map(|c| c.into()).collect());
-        } else {
-            map.add_node(l.parse()?);
+            if map.is_empty() {
+                map.add_directions(parse_glyphs(&l, mode).map_err(|e| e.at_line(index + 1))?);
+            } else {
+                map.add_node(l.parse().map_err(|e: ParseError| e.at_line(index + 1))?);
+            }
         }
+
+        Ok(map)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.steps_between("AAA".to_string(), "ZZZ".to_string()))
     }
 
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.steps_to_multiple())
+    }
+}
+
+pub fn total_steps(input: impl Read, multiple: bool) -> Result<u64, Box<dyn Error>> {
+    let map = Map::parse(input)?;
+
     if multiple {
         Ok(map.steps_to_multiple())
     } else {
@@ -223,4 +233,18 @@ XXX = (XXX, XXX)";
 
         Ok(())
     }
+
+    #[test]
+    fn reject_unknown_direction_when_strict() -> Result<(), Box<dyn Error>> {
+        let input = "LLX
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+        assert_eq!(6, total_steps(input.as_bytes(), false)?);
+        assert!(Map::parse_with(input.as_bytes(), ParseMode::Strict).is_err());
+
+        Ok(())
+    }
 }
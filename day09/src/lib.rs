@@ -1,6 +1,13 @@
 use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 
+use common::{parse_number_with, ParseMode, Solution};
+
+pub struct Report {
+    histories: Vec<Vec<i64>>,
+}
+
 fn extrapolate(values: &[i64], backwards: bool) -> i64 {
     if values.iter().all(|&value| value == values[0]) {
         return values[0];
@@ -22,21 +29,44 @@ fn extrapolate(values: &[i64], backwards: bool) -> i64 {
     values[if backwards { 0 } else { values.len() - 1 }] + difference
 }
 
-pub fn sum_values(input: impl Read, backwards: bool) -> Result<i64, Box<dyn Error>> {
-    let mut sum = 0;
+impl Report {
+    fn sum_values(&self, backwards: bool) -> i64 {
+        self.histories
+            .iter()
+            .map(|values| extrapolate(values, backwards))
+            .sum()
+    }
+}
+
+impl Solution for Report {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut histories = vec![];
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
 
-        let values = l
-            .split_ascii_whitespace()
-            .map(|value| value.parse().unwrap_or_default())
-            .collect::<Vec<_>>();
+            histories.push(
+                l.split_ascii_whitespace()
+                    .map(|value| parse_number_with(value, &l, mode))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| e.at_line(index + 1))?,
+            );
+        }
 
-        sum += extrapolate(&values, backwards);
+        Ok(Self { histories })
     }
 
-    Ok(sum)
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_values(false))
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_values(true))
+    }
+}
+
+pub fn sum_values(input: impl Read, backwards: bool) -> Result<i64, Box<dyn Error>> {
+    Ok(Report::parse(input)?.sum_values(backwards))
 }
 
 #[cfg(test)]
@@ -1,5 +1,9 @@
 use std::error::Error;
-use std::io::{self, BufRead, Read};
+use std::fmt::Display;
+use std::io::Read;
+
+use common::{FromGlyph, ParseMode, Solution};
+use grid::{manhattan_distance, Direction, Grid};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -13,33 +17,31 @@ enum Tile {
     Start,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
+pub struct Map {
+    tiles: Grid<Tile>,
+    vertices: Vec<(i64, i64)>,
+    path: Vec<(i64, i64)>,
 }
 
-pub struct Map {
-    tiles: Vec<Tile>,
-    width: usize,
-    vertices: Vec<(i32, i32)>,
-    path: Vec<(i32, i32)>,
+impl FromGlyph for Tile {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '|' => Some(Tile::NorthSouth),
+            '-' => Some(Tile::EastWest),
+            'L' => Some(Tile::NorthEast),
+            'J' => Some(Tile::NorthWest),
+            '7' => Some(Tile::SouthWest),
+            'F' => Some(Tile::SouthEast),
+            'S' => Some(Tile::Start),
+            '.' => Some(Tile::Ground),
+            _ => None,
+        }
+    }
 }
 
 impl From<char> for Tile {
     fn from(value: char) -> Self {
-        match value {
-            '|' => Tile::NorthSouth,
-            '-' => Tile::EastWest,
-            'L' => Tile::NorthEast,
-            'J' => Tile::NorthWest,
-            '7' => Tile::SouthWest,
-            'F' => Tile::SouthEast,
-            'S' => Tile::Start,
-            _ => Tile::Ground,
-        }
+        Tile::from_glyph(value).unwrap_or(Tile::Ground)
     }
 }
 
@@ -53,94 +55,50 @@ impl Tile {
 
     fn change_direction(&self, direction: Direction) -> Option<Direction> {
         match direction {
-            Direction::North => match *self {
-                Tile::NorthSouth => Some(Direction::North),
-                Tile::SouthWest => Some(Direction::West),
-                Tile::SouthEast => Some(Direction::East),
+            Direction::Up => match *self {
+                Tile::NorthSouth => Some(Direction::Up),
+                Tile::SouthWest => Some(Direction::Left),
+                Tile::SouthEast => Some(Direction::Right),
                 _ => None,
             },
-            Direction::South => match *self {
-                Tile::NorthSouth => Some(Direction::South),
-                Tile::NorthEast => Some(Direction::East),
-                Tile::NorthWest => Some(Direction::West),
+            Direction::Down => match *self {
+                Tile::NorthSouth => Some(Direction::Down),
+                Tile::NorthEast => Some(Direction::Right),
+                Tile::NorthWest => Some(Direction::Left),
                 _ => None,
             },
-            Direction::East => match *self {
-                Tile::EastWest => Some(Direction::East),
-                Tile::NorthWest => Some(Direction::North),
-                Tile::SouthWest => Some(Direction::South),
+            Direction::Right => match *self {
+                Tile::EastWest => Some(Direction::Right),
+                Tile::NorthWest => Some(Direction::Up),
+                Tile::SouthWest => Some(Direction::Down),
                 _ => None,
             },
-            Direction::West => match *self {
-                Tile::EastWest => Some(Direction::West),
-                Tile::NorthEast => Some(Direction::North),
-                Tile::SouthEast => Some(Direction::South),
+            Direction::Left => match *self {
+                Tile::EastWest => Some(Direction::Left),
+                Tile::NorthEast => Some(Direction::Up),
+                Tile::SouthEast => Some(Direction::Down),
                 _ => None,
             },
         }
     }
 }
 
-impl Direction {
-    fn step(&self, (x, y): (i32, i32)) -> (i32, i32) {
-        match *self {
-            Direction::North => (x, y - 1),
-            Direction::South => (x, y + 1),
-            Direction::East => (x + 1, y),
-            Direction::West => (x - 1, y),
-        }
-    }
-}
-
 impl Map {
-    fn new() -> Self {
+    fn new(tiles: Grid<Tile>) -> Self {
         Self {
-            tiles: vec![],
-            width: 0,
+            tiles,
             vertices: vec![],
             path: vec![],
         }
     }
 
-    fn add_row(&mut self, mut row: Vec<Tile>) {
-        if self.width == 0 {
-            self.width = row.len();
-        }
-
-        self.tiles.append(&mut row);
-    }
-
-    fn get_tile(&self, (x, y): (i32, i32)) -> Option<Tile> {
-        if x < 0
-            || y < 0
-            || x >= self.width as i32
-            || y >= self.tiles.len() as i32 / self.width as i32
-        {
-            return None;
-        }
-
-        Some(self.tiles[(y * self.width as i32 + x) as usize])
-    }
-
-    fn get_coords(&self, index: usize) -> (i32, i32) {
-        (
-            index as i32 % self.width as i32,
-            index as i32 / self.width as i32,
-        )
-    }
-
-    fn distance(&self, (x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> i32 {
-        (x2 - x1).abs() + (y2 - y1).abs()
+    fn get_tile(&self, coords: (i64, i64)) -> Option<Tile> {
+        self.tiles.get_signed(coords).copied()
     }
 
-    fn valid_direction(&self, coords: (i32, i32)) -> Option<Direction> {
-        for direction in [
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ] {
-            if let Some(tile) = self.get_tile(direction.step(coords)) {
+    fn valid_direction(&self, coords: (i64, i64)) -> Option<Direction> {
+        for direction in Direction::ALL {
+            if let Some(tile) = self.get_tile(direction.step_signed(coords)?) {
                 if tile.change_direction(direction).is_some() {
                     return Some(direction);
                 }
@@ -151,8 +109,8 @@ impl Map {
     }
 
     fn find_vertices(&mut self) -> Option<()> {
-        if let Some(start_index) = self.tiles.iter().position(|&tile| tile == Tile::Start) {
-            let start_coords = self.get_coords(start_index);
+        if let Some((x, y)) = self.tiles.position(|&tile| tile == Tile::Start) {
+            let start_coords = (x as i64, y as i64);
 
             let mut coords = start_coords;
             let mut direction = self.valid_direction(coords)?;
@@ -161,7 +119,7 @@ impl Map {
             self.vertices.push(coords);
 
             loop {
-                coords = direction.step(coords);
+                coords = direction.step_signed(coords)?;
 
                 if coords == start_coords {
                     return Some(());
@@ -190,9 +148,9 @@ impl Map {
         let steps = self
             .vertices
             .windows(2)
-            .map(|coords| self.distance(coords[0], coords[1]))
-            .sum::<i32>() as u32
-            + self.distance(self.vertices[self.vertices.len() - 1], self.vertices[0]) as u32;
+            .map(|coords| manhattan_distance(coords[0], coords[1]))
+            .sum::<u128>() as u32
+            + manhattan_distance(self.vertices[self.vertices.len() - 1], self.vertices[0]) as u32;
 
         Some(steps / 2)
     }
@@ -205,12 +163,12 @@ impl Map {
         let mut enclosed = 0;
         let mut edges = vec![];
 
-        for y in 0..(self.tiles.len() / self.width) as i32 {
+        for y in 0..self.tiles.height() as i64 {
             for (index, vertex) in self
                 .vertices
                 .iter()
                 .enumerate()
-                .filter(|(_, vertex)| vertex.1 == y as i32)
+                .filter(|(_, vertex)| vertex.1 == y)
             {
                 let prev_index = if index == 0 {
                     self.vertices.len() - 1
@@ -234,12 +192,12 @@ impl Map {
                 }
             }
 
-            edges.retain(|edge: &(i32, i32, i32)| edge.2 > y);
+            edges.retain(|edge: &(i64, i64, i64)| edge.2 > y);
             edges.sort();
 
             let mut inside = false;
 
-            for x in 0..self.width as i32 {
+            for x in 0..self.tiles.width() as i64 {
                 let in_path = self.path.contains(&(x, y));
 
                 if edges.iter().any(|edge| edge.0 == x) {
@@ -256,16 +214,26 @@ impl Map {
     }
 }
 
-pub fn build_map(input: impl Read) -> Result<Map, Box<dyn Error>> {
-    let mut map = Map::new();
+impl Solution for Map {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut map = Map::new(Grid::parse_with(input, mode)?);
+
+        map.find_vertices();
 
-    for line in io::BufReader::new(input).lines() {
-        map.add_row(line?.chars().map(|c| c.into()).collect());
+        Ok(map)
     }
 
-    map.find_vertices();
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.steps_to_furthest().unwrap_or_default())
+    }
 
-    Ok(map)
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.enclosed_tiles().unwrap_or_default())
+    }
+}
+
+pub fn build_map(input: impl Read) -> Result<Map, Box<dyn Error>> {
+    Map::parse(input)
 }
 
 #[cfg(test)]
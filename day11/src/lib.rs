@@ -1,75 +1,106 @@
 use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 
-pub fn sum_lengths(input: impl Read, rate: u64) -> Result<u64, Box<dyn Error>> {
-    let mut sum = 0;
-
-    let mut galaxies = vec![];
-    let mut y = 0u64;
+use common::{ParseError, ParseMode, Solution};
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+pub struct Image {
+    galaxies: Vec<(u64, u64)>,
+}
 
-        for (x, char) in l.chars().enumerate() {
-            if char == '#' {
-                galaxies.push((x as u64, y));
+impl Image {
+    fn sum_lengths(&self, rate: u64) -> u64 {
+        let galaxies = &self.galaxies;
+        let mut sum = 0;
+
+        let mut non_empty_rows = galaxies.iter().map(|galaxy| galaxy.1).collect::<Vec<_>>();
+        let mut non_empty_cols = galaxies.iter().map(|galaxy| galaxy.0).collect::<Vec<_>>();
+
+        non_empty_rows.sort();
+        non_empty_rows.dedup();
+
+        non_empty_cols.sort();
+        non_empty_cols.dedup();
+
+        let rate = rate - 1;
+
+        for (i, galaxy_a) in galaxies.iter().enumerate() {
+            for galaxy_b in galaxies[(i + 1)..].iter() {
+                let min_x;
+                let max_x;
+                let min_y;
+                let max_y;
+
+                if galaxy_a.0 < galaxy_b.0 {
+                    min_x = galaxy_a.0;
+                    max_x = galaxy_b.0;
+                } else {
+                    min_x = galaxy_b.0;
+                    max_x = galaxy_a.0;
+                }
+
+                if galaxy_a.1 < galaxy_b.1 {
+                    min_y = galaxy_a.1;
+                    max_y = galaxy_b.1;
+                } else {
+                    min_y = galaxy_b.1;
+                    max_y = galaxy_a.1;
+                }
+
+                sum += (max_x - min_x)
+                    + ((max_x - min_x)
+                        - (min_x..max_x)
+                            .filter(|x| non_empty_cols.contains(x))
+                            .count() as u64)
+                        * rate;
+
+                sum += (max_y - min_y)
+                    + ((max_y - min_y)
+                        - (min_y..max_y)
+                            .filter(|y| non_empty_rows.contains(y))
+                            .count() as u64)
+                        * rate;
             }
         }
 
-        y += 1;
+        sum
     }
+}
 
-    let mut non_empty_rows = galaxies.iter().map(|galaxy| galaxy.1).collect::<Vec<_>>();
-    let mut non_empty_cols = galaxies.iter().map(|galaxy| galaxy.0).collect::<Vec<_>>();
-
-    non_empty_rows.sort();
-    non_empty_rows.dedup();
-
-    non_empty_cols.sort();
-    non_empty_cols.dedup();
+impl Solution for Image {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut galaxies = vec![];
 
-    let rate = rate - 1;
+        for (y, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
 
-    for (i, galaxy_a) in galaxies.iter().enumerate() {
-        for galaxy_b in galaxies[(i + 1)..].iter() {
-            let min_x;
-            let max_x;
-            let min_y;
-            let max_y;
+            for (x, (index, char)) in l.char_indices().enumerate() {
+                if char == '#' {
+                    galaxies.push((x as u64, y as u64));
+                } else if char != '.' && mode == ParseMode::Strict {
+                    let glyph = &l[index..index + char.len_utf8()];
 
-            if galaxy_a.0 < galaxy_b.0 {
-                min_x = galaxy_a.0;
-                max_x = galaxy_b.0;
-            } else {
-                min_x = galaxy_b.0;
-                max_x = galaxy_a.0;
+                    return Err(ParseError::invalid("character", &l, glyph)
+                        .at_line(y + 1)
+                        .into());
+                }
             }
+        }
 
-            if galaxy_a.1 < galaxy_b.1 {
-                min_y = galaxy_a.1;
-                max_y = galaxy_b.1;
-            } else {
-                min_y = galaxy_b.1;
-                max_y = galaxy_a.1;
-            }
+        Ok(Self { galaxies })
+    }
 
-            sum += (max_x - min_x)
-                + ((max_x - min_x)
-                    - (min_x..max_x)
-                        .filter(|x| non_empty_cols.contains(&x))
-                        .count() as u64)
-                    * rate;
-
-            sum += (max_y - min_y)
-                + ((max_y - min_y)
-                    - (min_y..max_y)
-                        .filter(|y| non_empty_rows.contains(&y))
-                        .count() as u64)
-                    * rate;
-        }
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_lengths(2))
     }
 
-    Ok(sum)
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_lengths(1_000_000))
+    }
+}
+
+pub fn sum_lengths(input: impl Read, rate: u64) -> Result<u64, Box<dyn Error>> {
+    Ok(Image::parse(input)?.sum_lengths(rate))
 }
 
 #[cfg(test)]
@@ -1,9 +1,13 @@
 use std::error::Error;
-use std::fmt::{self, Debug};
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+use common::{
+    next_token, parse_glyphs, parse_number_with, FromGlyph, ParseError, ParseMode, Solution,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 enum Condition {
     Operational,
     Damaged,
@@ -15,222 +19,139 @@ struct Row {
     damaged_groups: Vec<usize>,
 }
 
-#[derive(Debug)]
-struct ParseRowError;
+pub struct Records {
+    rows: Vec<Row>,
+}
 
-impl fmt::Display for ParseRowError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse row")
+impl FromGlyph for Condition {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '.' => Some(Condition::Operational),
+            '#' => Some(Condition::Damaged),
+            '?' => Some(Condition::Unknown),
+            _ => None,
+        }
     }
 }
 
-impl Error for ParseRowError {}
-
 impl From<char> for Condition {
     fn from(value: char) -> Self {
-        match value {
-            '.' => Condition::Operational,
-            '#' => Condition::Damaged,
-            _ => Condition::Unknown,
-        }
+        Condition::from_glyph(value).unwrap_or(Condition::Unknown)
     }
 }
 
 impl FromStr for Row {
-    type Err = ParseRowError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split_ascii_whitespace();
-
-        let mut springs = parts
-            .next()
-            .ok_or(ParseRowError)?.to_string();
-
-        springs += "?";
-
-        let springs = springs.repeat(5).strip_suffix("?").unwrap()
-            .chars()
-            .map(|char| char.into())
-            .collect::<Vec<_>>();
+        Self::parse_with(s, ParseMode::Lenient)
+    }
+}
 
-        let mut damaged_groups = parts
-            .next()
-            .ok_or(ParseRowError)?.to_string();
+impl Row {
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
+        let mut parts = s.split_ascii_whitespace();
 
-        damaged_groups += ",";
+        let label = next_token(&mut parts, "springs", s)?;
+        let springs = parse_glyphs(label, mode).map_err(|e| e.within(s, label))?;
 
-        let damaged_groups = damaged_groups.repeat(5).strip_suffix(",").unwrap()
+        let damaged_groups = next_token(&mut parts, "damaged groups", s)?
             .split(",")
-            .map(|group| group.parse().unwrap_or_default())
-            .collect::<Vec<_>>();
+            .map(|group| parse_number_with(group, s, mode))
+            .collect::<Result<_, _>>()?;
 
         Ok(Self {
             springs,
             damaged_groups,
         })
     }
-}
 
-impl Debug for Condition {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            Condition::Operational => write!(f, "."),
-            Condition::Damaged => write!(f, "D"),
-            Condition::Unknown => write!(f, "?"),
-        }
-    }
-}
+    fn unfold(&self, factor: usize) -> Self {
+        let mut springs = self.springs.clone();
 
-impl Row {
-    fn is_valid(&self, springs: &Vec<Condition>) -> bool {
-        if springs
-        .split(|&spring| spring == Condition::Operational)
-        .filter(|group| !group.is_empty())
-        .count() != self.damaged_groups.len() {
-            return false;
+        for _ in 1..factor {
+            springs.push(Condition::Unknown);
+            springs.extend_from_slice(&self.springs);
         }
-        springs
-            .split(|&spring| spring == Condition::Operational)
-            .filter(|group| !group.is_empty())
-            .cycle()
-            .zip(self.damaged_groups.iter())
-            .all(|(spring_group, &damaged_group)| spring_group.len() == damaged_group)
-    }
 
-    fn count_arrangements_from(&self, curr_springs: Vec<Condition>, index: usize, damaged_group: usize, a: &mut Vec<Vec<Condition>>) -> u64 {
-        for _ in 0..index {
-            // print!(" ");
-        }
-        // println!("try: {:?} {} {}", curr_springs, index, damaged_group);
-        if index >= self.springs.len() || damaged_group >= self.damaged_groups.len() {
-            return 0;
+        Self {
+            springs,
+            damaged_groups: self.damaged_groups.repeat(factor),
         }
+    }
 
-        let mut count = 0;
+    fn fits_group(&self, start: usize, len: usize) -> bool {
+        let end = start + len;
 
-        'outer: for (i, &spring) in curr_springs[index..].iter().enumerate() {
-            if index + i + self.damaged_groups[damaged_group] > curr_springs.len() {
-                continue;
-            }
+        end <= self.springs.len()
+            && self.springs[start..end]
+                .iter()
+                .all(|&spring| spring != Condition::Operational)
+            && self.springs.get(end) != Some(&Condition::Damaged)
+    }
 
-            // println!("{index} {i} {damaged_group}");
-            for j in 0..self.damaged_groups[damaged_group] {
-                if curr_springs[index + i + j] == Condition::Operational {
-                    // println!("already set {i}");
-                    continue;
-                }
-            }
+    fn count_arrangements(&self) -> u64 {
+        let springs = self.springs.len();
+        let groups = self.damaged_groups.len();
 
-            if index + i + self.damaged_groups[damaged_group] + 1 < curr_springs.len()
-                && curr_springs[index + i + self.damaged_groups[damaged_group]] == Condition::Damaged
-            {
-                // println!("a");
-                continue;
-            }
+        let mut counts = vec![vec![0; groups + 1]; springs + 2];
+        counts[springs][groups] = 1;
+        counts[springs + 1][groups] = 1;
 
-            let mut new_springs = curr_springs.clone();
+        for i in (0..springs).rev() {
+            for g in 0..=groups {
+                let mut count = 0;
 
-            if i > 0 {
-                for j in 1..i {
-                    if new_springs[index + j - 1] == Condition::Unknown {
-                        new_springs[index + j - 1] = Condition::Operational;
-                    }
+                if self.springs[i] != Condition::Damaged {
+                    count += counts[i + 1][g];
                 }
-            }
 
-            // if index + i + self.damaged_groups[damaged_group] + 1 < self.springs.len()
-            //     && new_springs[index + i + self.damaged_groups[damaged_group] + 1] == Condition::Damaged
-            // {
-                // println!("c {index} {i} {damaged_group}");
-                // continue;
-            // }
-
-            for j in 0..self.damaged_groups[damaged_group] {
-                if index + i + j >= self.springs.len() || new_springs[index + i + j] == Condition::Operational {
-                    // println!("a {index} {i} {j}");
-                    continue 'outer;
+                if self.springs[i] != Condition::Operational
+                    && g < groups
+                    && self.fits_group(i, self.damaged_groups[g])
+                {
+                    count += counts[i + self.damaged_groups[g] + 1][g + 1];
                 }
 
-                new_springs[index + i + j] = Condition::Damaged;
+                counts[i][g] = count;
             }
+        }
 
-            if damaged_group == self.damaged_groups.len() - 1 {
-                for j in 0..new_springs.len() {
-                    if new_springs[j] == Condition::Unknown {
-                        new_springs[j] = Condition::Operational;
-                    }
-                }
-            }
-
-            if index + i > 0 {
-                if new_springs[index + i - 1] == Condition::Damaged {
-                    // println!("b");
-                    continue 'outer;
-                }
-
-                new_springs[index + i - 1] = Condition::Operational;
-            }
+        counts[0][0]
+    }
+}
 
-            if index + i + self.damaged_groups[damaged_group] > self.springs.len()
-                // && new_springs[index + i + self.damaged_groups[damaged_group]] == Condition::Damaged
-            {
-                // continue;
-                new_springs[index + i + self.damaged_groups[damaged_group]] = Condition::Operational;
-            }
+impl Records {
+    fn sum_counts(&self, unfold: usize) -> u64 {
+        self.rows
+            .iter()
+            .map(|row| row.unfold(unfold).count_arrangements())
+            .sum()
+    }
+}
 
+impl Solution for Records {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut rows = vec![];
 
-            if new_springs.iter().filter(|&&spring| spring == Condition::Unknown).count() > 0 {
-                count += self.count_arrangements_from(new_springs, index + i + self.damaged_groups[damaged_group], damaged_group + 1, a);
-            } else if self.is_valid(&new_springs) {
-                a.push(new_springs);
-                // println!("valid: {:?} {:?}", new_springs, self.damaged_groups);
-                count += 1;
-            } else {
-                // println!("here");
-                // if damaged_group == self.damaged_groups.len() - 1 {
-                //     for j in 0..new_springs.len() {
-                //         if new_springs[j] == Condition::Unknown {
-                //             new_springs[j] = Condition::Operational;
-                //         }
-                //     }
-                // }
-
-                // if self.is_valid(&new_springs) {
-                //     println!("valid: {:?} {:?}", new_springs, self.damaged_groups);
-                //     count += 1;
-                // }
-                // println!("not valid");
-            }
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            rows.push(Row::parse_with(&line?, mode).map_err(|e| e.at_line(index + 1))?);
         }
 
-        count
+        Ok(Self { rows })
     }
 
-    fn count_arrangements(&self) -> u64 {
-        let mut a = vec![];
-        println!("start: {:?} {:?}", self.springs, self.damaged_groups);
-        let count = self.count_arrangements_from(self.springs.clone(), 0, 0, &mut a);
-        a.sort();
-        a.dedup();
-        return a.len() as u64;
-        if a.len() != count as usize {
-            panic!("aaaaa");
-        }
-        println!("got {count}");
-        count
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_counts(1))
     }
-}
-
-pub fn sum_counts(input: impl Read) -> Result<u64, Box<dyn Error>> {
-    let mut sum = 0;
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
-
-        sum += l.parse::<Row>()?.count_arrangements();
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_counts(5))
     }
+}
 
-    Ok(sum)
+pub fn sum_counts(input: impl Read, unfold: usize) -> Result<u64, Box<dyn Error>> {
+    Ok(Records::parse(input)?.sum_counts(unfold))
 }
 
 #[cfg(test)]
@@ -244,12 +165,16 @@ mod tests {
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
 
-    // const INPUT: &str = "?#?#?#?#?#?#?#? 1,3,1,6";
-    // const INPUT: &str = "???.###????.###????.###????.###????.### 1,1,3,1,1,3,1,1,3,1,1,3,1,1,3";
+    #[test]
+    fn sum_counts_folded() -> Result<(), Box<dyn Error>> {
+        assert_eq!(21, sum_counts(INPUT.as_bytes(), 1)?);
+
+        Ok(())
+    }
 
     #[test]
-    fn sum_values_forward() -> Result<(), Box<dyn Error>> {
-        assert_eq!(21, sum_counts(INPUT.as_bytes())?);
+    fn sum_counts_unfolded() -> Result<(), Box<dyn Error>> {
+        assert_eq!(525152, sum_counts(INPUT.as_bytes(), 5)?);
 
         Ok(())
     }
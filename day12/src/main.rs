@@ -6,7 +6,12 @@ use day12::*;
 fn main() -> Result<(), Box<dyn Error>> {
     println!(
         "Sum of possible arrangement counts: {}",
-        sum_counts(File::open("input.txt")?)?
+        sum_counts(File::open("input.txt")?, 1)?
+    );
+
+    println!(
+        "Sum of possible arrangement counts after unfolding: {}",
+        sum_counts(File::open("input.txt")?, 5)?
     );
 
     Ok(())
@@ -1,6 +1,10 @@
 use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 
+use common::{FromGlyph, ParseMode, Solution};
+use grid::Grid;
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Ash,
@@ -8,55 +12,51 @@ enum Tile {
 }
 
 struct Pattern {
-    tiles: Vec<Tile>,
-    width: usize,
+    tiles: Grid<Tile>,
+}
+
+pub struct Notes {
+    patterns: Vec<Pattern>,
+}
+
+impl FromGlyph for Tile {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '.' => Some(Tile::Ash),
+            '#' => Some(Tile::Rock),
+            _ => None,
+        }
+    }
 }
 
 impl From<char> for Tile {
     fn from(value: char) -> Self {
-        match value {
-            '.' => Tile::Ash,
-            _ => Tile::Rock,
-        }
+        Tile::from_glyph(value).unwrap_or(Tile::Rock)
     }
 }
 
 impl Pattern {
     fn new() -> Self {
-        Self {
-            tiles: vec![],
-            width: 0,
-        }
+        Self { tiles: Grid::new() }
     }
 
-    fn add_row(&mut self, mut row: Vec<Tile>) {
-        if self.width == 0 {
-            self.width = row.len();
-        }
-
-        self.tiles.append(&mut row);
+    fn width(&self) -> usize {
+        self.tiles.width()
     }
 
     fn height(&self) -> usize {
-        self.tiles.len() / self.width
-    }
-
-    fn tiles_in_col<'a>(&'a self, x: usize) -> impl Iterator<Item = &Tile> + 'a {
-        self.tiles.iter().skip(x).step_by(self.width)
-    }
-
-    fn tiles_in_row<'a>(&'a self, y: usize) -> impl Iterator<Item = &Tile> + 'a {
-        self.tiles.iter().skip(y * self.width).take(self.width)
+        self.tiles.height()
     }
 
     fn reflected_cols(&self, differences: usize) -> usize {
-        'outer: for x in 0..(self.width - 1) {
+        'outer: for x in 0..(self.width() - 1) {
             let mut diff = 0;
 
-            for i in 0..(x + 1).min(self.width - x - 1) {
+            for i in 0..(x + 1).min(self.width() - x - 1) {
                 diff += self
-                    .tiles_in_col(x - i)
-                    .zip(self.tiles_in_col(x + 1 + i))
+                    .tiles
+                    .column(x - i)
+                    .zip(self.tiles.column(x + 1 + i))
                     .filter(|(a, b)| a != b)
                     .count();
 
@@ -79,8 +79,9 @@ impl Pattern {
 
             for i in 0..(y + 1).min(self.height() - y - 1) {
                 diff += self
-                    .tiles_in_row(y - i)
-                    .zip(self.tiles_in_row(y + 1 + i))
+                    .tiles
+                    .row(y - i)
+                    .zip(self.tiles.row(y + 1 + i))
                     .filter(|(a, b)| a != b)
                     .count();
 
@@ -102,24 +103,50 @@ impl Pattern {
     }
 }
 
-pub fn sum_patterns(input: impl Read, differences: usize) -> Result<usize, Box<dyn Error>> {
-    let mut sum = 0;
-    let mut pattern = Pattern::new();
-
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+impl Notes {
+    fn sum_patterns(&self, differences: usize) -> usize {
+        self.patterns
+            .iter()
+            .map(|pattern| pattern.summarize(differences))
+            .sum()
+    }
+}
 
-        if l.is_empty() {
-            sum += pattern.summarize(differences);
-            pattern = Pattern::new();
-        } else {
-            pattern.add_row(l.chars().map(|c| c.into()).collect());
+impl Solution for Notes {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut patterns = vec![];
+        let mut pattern = Pattern::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
+
+            if l.is_empty() {
+                patterns.push(pattern);
+                pattern = Pattern::new();
+            } else {
+                pattern
+                    .tiles
+                    .parse_row(&l, mode)
+                    .map_err(|e| e.at_line(index + 1))?;
+            }
         }
+
+        patterns.push(pattern);
+
+        Ok(Self { patterns })
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_patterns(0))
     }
 
-    sum += pattern.summarize(differences);
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_patterns(1))
+    }
+}
 
-    Ok(sum)
+pub fn sum_patterns(input: impl Read, differences: usize) -> Result<usize, Box<dyn Error>> {
+    Ok(Notes::parse(input)?.sum_patterns(differences))
 }
 
 #[cfg(test)]
@@ -1,5 +1,11 @@
 use std::error::Error;
-use std::io::{self, BufRead, Read};
+use std::fmt::Display;
+use std::io::Read;
+
+use common::{FromGlyph, ParseMode, Solution};
+use grid::Grid;
+
+pub use grid::Direction;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Space {
@@ -8,80 +14,68 @@ enum Space {
     CubeRock,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Direction {
-    North,
-    South,
-    East,
-    West,
+#[derive(Clone)]
+pub struct Platform {
+    spaces: Grid<Space>,
 }
 
-pub struct Platform {
-    spaces: Vec<Space>,
-    width: usize,
+impl FromGlyph for Space {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            'O' => Some(Space::RoundRock),
+            '#' => Some(Space::CubeRock),
+            '.' => Some(Space::Empty),
+            _ => None,
+        }
+    }
 }
 
 impl From<char> for Space {
     fn from(value: char) -> Self {
-        match value {
-            'O' => Space::RoundRock,
-            '#' => Space::CubeRock,
-            _ => Space::Empty,
-        }
+        Space::from_glyph(value).unwrap_or(Space::Empty)
     }
 }
 
 impl Platform {
-    fn new() -> Self {
-        Self {
-            spaces: vec![],
-            width: 0,
-        }
-    }
-
-    fn add_row(&mut self, mut row: Vec<Space>) {
-        if self.width == 0 {
-            self.width = row.len();
-        }
-
-        self.spaces.append(&mut row);
+    fn width(&self) -> usize {
+        self.spaces.width()
     }
 
     fn height(&self) -> usize {
-        self.spaces.len() / self.width
+        self.spaces.height()
     }
 
     fn cols(&self, direction: Direction) -> usize {
-        if direction == Direction::North || direction == Direction::South {
-            self.width
+        if direction.is_vertical() {
+            self.width()
         } else {
             self.height()
         }
     }
 
     fn rows(&self, direction: Direction) -> usize {
-        if direction == Direction::North || direction == Direction::South {
+        if direction.is_vertical() {
             self.height()
         } else {
-            self.width
+            self.width()
         }
     }
 
     fn x_coord(&self, direction: Direction, col: usize, row: usize) -> usize {
         match direction {
-            Direction::North => col,
-            Direction::South => col,
-            Direction::East => self.width - row - 1,
-            Direction::West => row,
+            Direction::Up => col,
+            Direction::Down => col,
+            Direction::Right => self.width() - row - 1,
+            Direction::Left => row,
         }
     }
 
     fn y_coord(&self, direction: Direction, col: usize, row: usize) -> usize {
         match direction {
-            Direction::North => row,
-            Direction::South => self.height() - row - 1,
-            Direction::East => col,
-            Direction::West => col,
+            Direction::Up => row,
+            Direction::Down => self.height() - row - 1,
+            Direction::Right => col,
+            Direction::Left => col,
         }
     }
 
@@ -89,14 +83,14 @@ impl Platform {
         let x = self.x_coord(direction, col, row);
         let y = self.y_coord(direction, col, row);
 
-        self.spaces[y * self.width + x]
+        self.spaces[(x, y)]
     }
 
     fn set_space(&mut self, direction: Direction, col: usize, row: usize, space: Space) {
         let x = self.x_coord(direction, col, row);
         let y = self.y_coord(direction, col, row);
 
-        self.spaces[y * self.width + x] = space;
+        self.spaces[(x, y)] = space;
     }
 
     pub fn tilt(&mut self, direction: Direction) {
@@ -148,10 +142,10 @@ impl Platform {
     }
 
     pub fn cycle(&mut self) {
-        self.tilt(Direction::North);
-        self.tilt(Direction::West);
-        self.tilt(Direction::South);
-        self.tilt(Direction::East);
+        self.tilt(Direction::Up);
+        self.tilt(Direction::Left);
+        self.tilt(Direction::Down);
+        self.tilt(Direction::Right);
     }
 
     pub fn load_after_cycles(&mut self, direction: Direction, cycles: usize) -> usize {
@@ -179,14 +173,29 @@ impl Platform {
     }
 }
 
-pub fn build_platform(input: impl Read) -> Result<Platform, Box<dyn Error>> {
-    let mut platform = Platform::new();
+impl Solution for Platform {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            spaces: Grid::parse_with(input, mode)?,
+        })
+    }
 
-    for line in io::BufReader::new(input).lines() {
-        platform.add_row(line?.chars().map(|c| c.into()).collect());
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        let mut platform = self.clone();
+        platform.tilt(Direction::Up);
+
+        Ok(platform.load(Direction::Up))
     }
 
-    Ok(platform)
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self
+            .clone()
+            .load_after_cycles(Direction::Up, 1_000_000_000))
+    }
+}
+
+pub fn build_platform(input: impl Read) -> Result<Platform, Box<dyn Error>> {
+    Platform::parse(input)
 }
 
 #[cfg(test)]
@@ -207,9 +216,9 @@ O.#..O.#.#
     #[test]
     fn load_on_north_after_one_tilt() -> Result<(), Box<dyn Error>> {
         let mut platform = build_platform(INPUT.as_bytes())?;
-        platform.tilt(Direction::North);
+        platform.tilt(Direction::Up);
 
-        assert_eq!(136, platform.load(Direction::North));
+        assert_eq!(136, platform.load(Direction::Up));
 
         Ok(())
     }
@@ -218,7 +227,7 @@ O.#..O.#.#
     fn load_on_north_after_many_cycles() -> Result<(), Box<dyn Error>> {
         assert_eq!(
             64,
-            build_platform(INPUT.as_bytes())?.load_after_cycles(Direction::North, 1_000_000_000)
+            build_platform(INPUT.as_bytes())?.load_after_cycles(Direction::Up, 1_000_000_000)
         );
 
         Ok(())
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::fmt;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
+use common::{next_token, parse_number, ParseError, ParseMode, Solution};
+
 enum Step {
     Add(String, usize),
     Remove(String),
@@ -18,39 +20,26 @@ struct Boxes {
     boxes: HashMap<usize, Vec<Lens>>,
 }
 
-#[derive(Debug)]
-struct ParseStepError;
-
-impl fmt::Display for ParseStepError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse step")
-    }
+pub struct InitializationSequence {
+    steps: Vec<String>,
 }
 
-impl Error for ParseStepError {}
-
 impl FromStr for Step {
-    type Err = ParseStepError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.contains("=") {
             let mut parts = s.split("=");
 
-            let label = parts.next().ok_or(ParseStepError)?.to_string();
-            let focal_length = parts
-                .next()
-                .ok_or(ParseStepError)?
-                .parse()
-                .map_err(|_| ParseStepError)?;
+            let label = next_token(&mut parts, "label", s)?.to_string();
+            let focal_length = parse_number(next_token(&mut parts, "focal length", s)?, s)?;
 
             return Ok(Step::Add(label, focal_length));
-        } else if s.ends_with("-") {
-            let label = s.strip_suffix("-").ok_or(ParseStepError)?.to_string();
-
-            return Ok(Step::Remove(label));
+        } else if let Some(label) = s.strip_suffix("-") {
+            return Ok(Step::Remove(label.to_string()));
         }
 
-        Err(ParseStepError)
+        Err(ParseError::missing("operation", s))
     }
 }
 
@@ -117,30 +106,48 @@ fn hash(string: &str) -> usize {
     value
 }
 
-pub fn sum_hash_values(input: impl Read) -> Result<usize, Box<dyn Error>> {
-    let mut sum = 0;
+impl InitializationSequence {
+    fn sum_hash_values(&self) -> usize {
+        self.steps.iter().map(|step| hash(step)).sum()
+    }
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+    fn focusing_power(&self) -> Result<usize, ParseError> {
+        let mut boxes = Boxes::new();
 
-        sum += l.split(",").map(|step| hash(step)).sum::<usize>();
-    }
+        for step in &self.steps {
+            boxes.process(&step.parse()?);
+        }
 
-    Ok(sum)
+        Ok(boxes.focusing_power())
+    }
 }
 
-pub fn focusing_power(input: impl Read) -> Result<usize, Box<dyn Error>> {
-    let mut boxes = Boxes::new();
-
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+impl Solution for InitializationSequence {
+    fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut steps = vec![];
 
-        for step in l.split(",") {
-            boxes.process(&step.parse()?);
+        for line in io::BufReader::new(input).lines() {
+            steps.extend(line?.split(',').map(String::from));
         }
+
+        Ok(Self { steps })
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_hash_values())
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.focusing_power()?)
     }
+}
+
+pub fn sum_hash_values(input: impl Read) -> Result<usize, Box<dyn Error>> {
+    Ok(InitializationSequence::parse(input)?.sum_hash_values())
+}
 
-    Ok(boxes.focusing_power())
+pub fn focusing_power(input: impl Read) -> Result<usize, Box<dyn Error>> {
+    Ok(InitializationSequence::parse(input)?.focusing_power()?)
 }
 
 #[cfg(test)]
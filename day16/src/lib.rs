@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::io::{self, BufRead, Read};
+use std::fmt::Display;
+use std::io::Read;
+
+use common::{FromGlyph, ParseMode, Solution};
+use grid::{Direction, Grid};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -11,14 +15,6 @@ enum Tile {
     HorizontalSplitter,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 #[derive(Clone, Copy, PartialEq, Eq)]
 struct Beam {
     x: usize,
@@ -26,20 +22,26 @@ struct Beam {
     direction: Direction,
 }
 
-struct Layout {
-    tiles: Vec<Tile>,
-    width: usize,
+pub struct Layout {
+    tiles: Grid<Tile>,
+}
+
+impl FromGlyph for Tile {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '/' => Some(Tile::ForwardMirror),
+            '\\' => Some(Tile::BackwardMirror),
+            '|' => Some(Tile::VerticalSplitter),
+            '-' => Some(Tile::HorizontalSplitter),
+            '.' => Some(Tile::Empty),
+            _ => None,
+        }
+    }
 }
 
 impl From<char> for Tile {
     fn from(value: char) -> Self {
-        match value {
-            '/' => Tile::ForwardMirror,
-            '\\' => Tile::BackwardMirror,
-            '|' => Tile::VerticalSplitter,
-            '-' => Tile::HorizontalSplitter,
-            _ => Tile::Empty,
-        }
+        Tile::from_glyph(value).unwrap_or(Tile::Empty)
     }
 }
 
@@ -62,19 +64,15 @@ impl Beam {
                 Direction::Left => self.direction = Direction::Up,
                 Direction::Right => self.direction = Direction::Down,
             },
-            Tile::VerticalSplitter => {
-                if self.direction == Direction::Left || self.direction == Direction::Right {
-                    self.direction = Direction::Up;
+            Tile::VerticalSplitter if self.direction.is_horizontal() => {
+                self.direction = Direction::Up;
 
-                    return Some(Beam::new(self.x, self.y, Direction::Down));
-                }
+                return Some(Beam::new(self.x, self.y, Direction::Down));
             }
-            Tile::HorizontalSplitter => {
-                if self.direction == Direction::Up || self.direction == Direction::Down {
-                    self.direction = Direction::Left;
+            Tile::HorizontalSplitter if self.direction.is_vertical() => {
+                self.direction = Direction::Left;
 
-                    return Some(Beam::new(self.x, self.y, Direction::Right));
-                }
+                return Some(Beam::new(self.x, self.y, Direction::Right));
             }
             _ => {}
         }
@@ -83,59 +81,24 @@ impl Beam {
     }
 
     fn advance(&mut self, layout: &Layout) -> bool {
-        match self.direction {
-            Direction::Up => {
-                if self.y > 0 {
-                    self.y -= 1;
-                } else {
-                    return false;
-                }
-            }
-            Direction::Down => {
-                if self.y < layout.height() - 1 {
-                    self.y += 1;
-                } else {
-                    return false;
-                }
-            }
-            Direction::Left => {
-                if self.x > 0 {
-                    self.x -= 1;
-                } else {
-                    return false;
-                }
-            }
-            Direction::Right => {
-                if self.x < layout.width - 1 {
-                    self.x += 1;
-                } else {
-                    return false;
-                }
-            }
-        }
+        if let Some((x, y)) = layout.tiles.step((self.x, self.y), self.direction) {
+            self.x = x;
+            self.y = y;
 
-        true
+            true
+        } else {
+            false
+        }
     }
 }
 
 impl Layout {
-    fn new() -> Self {
-        Self {
-            tiles: vec![],
-            width: 0,
-        }
-    }
-
-    fn add_row(&mut self, mut row: Vec<Tile>) {
-        if self.width == 0 {
-            self.width = row.len();
-        }
-
-        self.tiles.append(&mut row);
+    fn width(&self) -> usize {
+        self.tiles.width()
     }
 
     fn height(&self) -> usize {
-        self.tiles.len() / self.width
+        self.tiles.height()
     }
 
     fn trace_beam(&self, beam: Beam, history: &mut HashMap<(usize, usize), [bool; 4]>) {
@@ -150,7 +113,7 @@ impl Layout {
 
             entry[beam.direction as usize] = true;
 
-            if let Some(b) = beam.reflect_and_split(self.tiles[beam.y * self.width + beam.x]) {
+            if let Some(b) = beam.reflect_and_split(self.tiles[(beam.x, beam.y)]) {
                 self.trace_beam(b, history);
             }
 
@@ -171,7 +134,7 @@ impl Layout {
     fn find_max_energized(&self) -> usize {
         let mut max_energized = 0;
 
-        for x in 0..self.width {
+        for x in 0..self.width() {
             let max = self
                 .find_energized(Beam::new(x, self.height() - 1, Direction::Up))
                 .max(self.find_energized(Beam::new(x, 0, Direction::Down)));
@@ -183,7 +146,7 @@ impl Layout {
 
         for y in 0..self.height() {
             let max = self
-                .find_energized(Beam::new(self.width - 1, 0, Direction::Left))
+                .find_energized(Beam::new(self.width() - 1, 0, Direction::Left))
                 .max(self.find_energized(Beam::new(0, y, Direction::Right)));
 
             if max > max_energized {
@@ -195,12 +158,24 @@ impl Layout {
     }
 }
 
-pub fn energized_tiles(input: impl Read, find_max: bool) -> Result<usize, Box<dyn Error>> {
-    let mut layout = Layout::new();
+impl Solution for Layout {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            tiles: Grid::parse_with(input, mode)?,
+        })
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.find_energized(Beam::new(0, 0, Direction::Right)))
+    }
 
-    for line in io::BufReader::new(input).lines() {
-        layout.add_row(line?.chars().map(|c| c.into()).collect());
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.find_max_energized())
     }
+}
+
+pub fn energized_tiles(input: impl Read, find_max: bool) -> Result<usize, Box<dyn Error>> {
+    let layout = Layout::parse(input)?;
 
     if find_max {
         Ok(layout.find_max_energized())
@@ -1,15 +1,11 @@
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use common::{parse_number_with, ParseMode, Solution};
+use grid::{Direction, Grid};
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Step {
@@ -18,52 +14,8 @@ struct Step {
     direction: Direction,
 }
 
-struct Map {
-    blocks: Vec<u32>,
-    width: usize,
-}
-
-impl Direction {
-    fn next_directions(&self) -> [Direction; 2] {
-        if *self == Direction::Up || *self == Direction::Down {
-            [Direction::Left, Direction::Right]
-        } else {
-            [Direction::Up, Direction::Down]
-        }
-    }
-
-    fn advance(&self, pos: (usize, usize), by: usize, map: &Map) -> Option<(usize, usize)> {
-        match self {
-            Direction::Up => {
-                if pos.0 > by - 1 {
-                    Some((pos.0 - by, pos.1))
-                } else {
-                    None
-                }
-            }
-            Direction::Down => {
-                if pos.0 + by < map.width {
-                    Some((pos.0 + by, pos.1))
-                } else {
-                    None
-                }
-            }
-            Direction::Left => {
-                if pos.1 > by - 1 {
-                    Some((pos.0, pos.1 - by))
-                } else {
-                    None
-                }
-            }
-            Direction::Right => {
-                if pos.1 + by < map.height() {
-                    Some((pos.0, pos.1 + by))
-                } else {
-                    None
-                }
-            }
-        }
-    }
+pub struct Map {
+    blocks: Grid<u32>,
 }
 
 impl Step {
@@ -78,12 +30,12 @@ impl Step {
     fn next_steps(&self, map: &Map, min_blocks: usize, max_blocks: usize) -> Vec<Step> {
         let mut steps = vec![];
 
-        for direction in self.direction.next_directions() {
+        for direction in [self.direction.turn_left(), self.direction.turn_right()] {
             let mut loss = 0;
 
             for i in 1..=max_blocks {
-                if let Some(pos) = direction.advance(self.pos, i, map) {
-                    loss += map.blocks[pos.1 * map.width + pos.0];
+                if let Some(pos) = map.blocks.step_by(self.pos, direction, i) {
+                    loss += map.blocks[pos];
 
                     if i >= min_blocks {
                         steps.push(Step::new(self.loss + loss, pos, direction));
@@ -97,23 +49,12 @@ impl Step {
 }
 
 impl Map {
-    fn new() -> Self {
-        Self {
-            blocks: vec![],
-            width: 0,
-        }
+    fn width(&self) -> usize {
+        self.blocks.width()
     }
 
     fn height(&self) -> usize {
-        self.blocks.len() / self.width
-    }
-
-    fn add_row(&mut self, mut row: Vec<u32>) {
-        if self.width == 0 {
-            self.width = row.len();
-        }
-
-        self.blocks.append(&mut row);
+        self.blocks.height()
     }
 
     fn find_min_loss(&self, min_blocks: usize, max_blocks: usize) -> u32 {
@@ -127,10 +68,11 @@ impl Map {
         to_visit.push(Reverse(Step::new(0, (0, 0), Direction::Down)));
 
         while let Some(Reverse(step)) = to_visit.pop() {
-            if step.pos.0 == self.width - 1 && step.pos.1 == self.height() - 1 {
-                if min_loss.is_none() || min_loss.unwrap_or_default() > step.loss {
-                    min_loss = Some(step.loss);
-                }
+            if step.pos.0 == self.width() - 1
+                && step.pos.1 == self.height() - 1
+                && (min_loss.is_none() || min_loss.unwrap_or_default() > step.loss)
+            {
+                min_loss = Some(step.loss);
             }
 
             if !visited.insert((step.pos, step.direction)) {
@@ -151,23 +93,36 @@ impl Map {
     }
 }
 
+impl Solution for Map {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut blocks = Grid::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
+
+            blocks
+                .parse_row_with(&l, |digit| parse_number_with(digit, &l, mode))
+                .map_err(|e| e.at_line(index + 1))?;
+        }
+
+        Ok(Self { blocks })
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.find_min_loss(1, 3))
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.find_min_loss(4, 10))
+    }
+}
+
 pub fn min_heat_loss(
     input: impl Read,
     min_blocks: usize,
     max_blocks: usize,
 ) -> Result<u32, Box<dyn Error>> {
-    let mut map = Map::new();
-
-    for line in io::BufReader::new(input).lines() {
-        map.add_row(
-            line?
-                .chars()
-                .map(|c| c.to_digit(10).unwrap_or_default())
-                .collect(),
-        );
-    }
-
-    Ok(map.find_min_loss(min_blocks, max_blocks))
+    Ok(Map::parse(input)?.find_min_loss(min_blocks, max_blocks))
 }
 
 #[cfg(test)]
@@ -214,4 +169,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn reject_non_digit_when_strict() {
+        let input = "2413
+32x5";
+
+        assert!(Map::parse(input.as_bytes()).is_ok());
+        assert_eq!(
+            Some(String::from(
+                "invalid number `x` at line 2, column 3
+  32x5
+    ^"
+            )),
+            Map::parse_with(input.as_bytes(), ParseMode::Strict)
+                .err()
+                .map(|e| e.to_string())
+        );
+    }
 }
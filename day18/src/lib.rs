@@ -1,16 +1,12 @@
 use std::error::Error;
-use std::fmt;
+use std::fmt::{self, Display};
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use common::{next_token, parse_number, ParseError, ParseErrorKind, ParseMode, Solution};
+use grid::Direction;
 
+#[derive(Clone, Copy)]
 struct Instruction {
     direction: Direction,
     length: i64,
@@ -21,81 +17,93 @@ struct Digger {
     pos: (i64, i64),
 }
 
+pub struct DigPlan {
+    instructions: Vec<Instruction>,
+    lines: Vec<String>,
+}
+
 #[derive(Debug)]
-struct ParseInstructionError;
+struct OverflowError((i64, i64));
 
-impl fmt::Display for ParseInstructionError {
+impl fmt::Display for OverflowError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse instruction")
+        write!(f, "dig plan leaves the representable area at {:?}", self.0)
     }
 }
 
-impl Error for ParseInstructionError {}
+impl Error for OverflowError {}
 
-impl FromStr for Direction {
-    type Err = ParseInstructionError;
+fn parse_direction(s: &str) -> Result<Direction, ParseError> {
+    match s {
+        "U" => Ok(Direction::Up),
+        "D" => Ok(Direction::Down),
+        "L" => Ok(Direction::Left),
+        "R" => Ok(Direction::Right),
+        _ => Err(ParseError::invalid("direction", s, s)),
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.chars().next().unwrap_or_default() {
-            'U' => Ok(Direction::Up),
-            'D' => Ok(Direction::Down),
-            'L' => Ok(Direction::Left),
-            'R' => Ok(Direction::Right),
-            _ => Err(ParseInstructionError),
-        }
+fn swap_direction(s: &str) -> Option<&'static str> {
+    match s {
+        "0" => Some("R"),
+        "1" => Some("D"),
+        "2" => Some("L"),
+        "3" => Some("U"),
+        _ => None,
     }
 }
 
 impl FromStr for Instruction {
-    type Err = ParseInstructionError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split_ascii_whitespace();
-        let direction = parts.next().ok_or(ParseInstructionError)?.parse()?;
-        let length = parts
-            .next()
-            .ok_or(ParseInstructionError)?
-            .parse()
-            .map_err(|_| ParseInstructionError)?;
 
-        Ok(Instruction { direction, length })
-    }
-}
+        let direction = next_token(&mut parts, "direction", s)?;
+        let direction = parse_direction(direction).map_err(|e| e.within(s, direction))?;
+        let length = parse_number(next_token(&mut parts, "length", s)?, s)?;
 
-impl Direction {
-    fn swap(s: &str) -> String {
-        match s.chars().next().unwrap_or_default() {
-            '0' => String::from("R"),
-            '1' => String::from("D"),
-            '2' => String::from("L"),
-            '3' => String::from("U"),
-            _ => String::new(),
-        }
+        Ok(Instruction { direction, length })
     }
 }
 
 impl Instruction {
-    fn swap(s: &str) -> String {
+    fn swap(s: &str) -> Result<String, ParseError> {
         let color = s
             .split_ascii_whitespace()
-            .last()
-            .unwrap_or_default()
+            .nth(2)
+            .ok_or_else(|| ParseError::missing("color", s))?
             .trim_start_matches("(#")
             .trim_end_matches(")");
 
-        let length = i64::from_str_radix(&color[..5], 16).unwrap_or_default();
-        let direction = Direction::swap(&color[5..]);
+        if color.len() != 6 || !color.is_ascii() {
+            return Err(ParseError::new(
+                ParseErrorKind::WrongCount {
+                    what: "color digits",
+                    expected: 6,
+                    found: color.chars().count(),
+                },
+                s,
+                color,
+            ));
+        }
+
+        let length = i64::from_str_radix(&color[..5], 16)
+            .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, s, &color[..5]))?;
+        let direction = swap_direction(&color[5..])
+            .ok_or_else(|| ParseError::invalid("direction", s, &color[5..]))?;
 
-        format!("{direction} {length}")
+        Ok(format!("{direction} {length}"))
     }
 
-    fn advance(&self, pos: (i64, i64)) -> (i64, i64) {
-        match self.direction {
-            Direction::Up => (pos.0, pos.1 - self.length),
-            Direction::Down => (pos.0, pos.1 + self.length),
-            Direction::Left => (pos.0 - self.length, pos.1),
-            Direction::Right => (pos.0 + self.length, pos.1),
-        }
+    fn swapped(s: &str) -> Result<Self, ParseError> {
+        Self::swap(s)?.parse()
+    }
+
+    fn advance(&self, pos: (i64, i64)) -> Result<(i64, i64), OverflowError> {
+        self.direction
+            .step_signed_by(pos, self.length)
+            .ok_or(OverflowError(pos))
     }
 }
 
@@ -107,9 +115,11 @@ impl Digger {
         }
     }
 
-    fn process(&mut self, instruction: Instruction) {
-        self.pos = instruction.advance(self.pos);
+    fn process(&mut self, instruction: Instruction) -> Result<(), OverflowError> {
+        self.pos = instruction.advance(self.pos)?;
         self.vertices.push(self.pos);
+
+        Ok(())
     }
 
     fn lava_held(&self) -> i64 {
@@ -200,22 +210,66 @@ impl Digger {
     }
 }
 
-pub fn total_lava_held(input: impl Read, swapped: bool) -> Result<i64, Box<dyn Error>> {
-    let mut digger = Digger::new();
+impl DigPlan {
+    fn swapped_instructions(&self) -> Result<Vec<Instruction>, ParseError> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(index, l)| Instruction::swapped(l).map_err(|e| e.at_line(index + 1)))
+            .collect()
+    }
+
+    fn lava_held(&self, swapped: bool) -> Result<i64, Box<dyn Error>> {
+        let mut digger = Digger::new();
 
-    for line in io::BufReader::new(input).lines() {
-        let mut l = line?;
+        let instructions = if swapped {
+            self.swapped_instructions()?
+        } else {
+            self.instructions.clone()
+        };
 
-        if swapped {
-            l = Instruction::swap(&l);
+        for instruction in instructions {
+            digger.process(instruction)?;
         }
 
-        let instruction = l.parse()?;
+        Ok(digger.lava_held())
+    }
+}
+
+impl Solution for DigPlan {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut instructions = vec![];
+        let mut lines = vec![];
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
+
+            instructions.push(l.parse().map_err(|e: ParseError| e.at_line(index + 1))?);
+
+            if mode == ParseMode::Strict {
+                Instruction::swapped(&l).map_err(|e| e.at_line(index + 1))?;
+            }
+
+            lines.push(l);
+        }
 
-        digger.process(instruction);
+        Ok(Self {
+            instructions,
+            lines,
+        })
     }
 
-    Ok(digger.lava_held())
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        self.lava_held(false)
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        self.lava_held(true)
+    }
+}
+
+pub fn total_lava_held(input: impl Read, swapped: bool) -> Result<i64, Box<dyn Error>> {
+    DigPlan::parse(input)?.lava_held(swapped)
 }
 
 #[cfg(test)]
@@ -250,4 +304,40 @@ U 2 (#7a21e3)";
 
         Ok(())
     }
+
+    #[test]
+    fn report_invalid_color() {
+        let plan = "R 6 (#70c710)
+D 5 (#0dc57x)";
+
+        let error = total_lava_held(plan.as_bytes(), true)
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(
+            "invalid direction `x` at line 2, column 12
+  D 5 (#0dc57x)
+             ^",
+            error
+        );
+    }
+
+    #[test]
+    fn ignore_color_until_swapped() -> Result<(), Box<dyn Error>> {
+        let plan = "R 2\nD 2 (#zz)\nL 2 (#000022)\nU 2 (#000023)";
+
+        assert_eq!(9, total_lava_held(plan.as_bytes(), false)?);
+        assert_eq!(
+            Some(String::from(
+                "missing color at line 1, column 4
+  R 2
+     ^"
+            )),
+            DigPlan::parse_with(plan.as_bytes(), ParseMode::Strict)
+                .err()
+                .map(|e| e.to_string())
+        );
+
+        Ok(())
+    }
 }
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::fmt;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
+use common::{next_token, parse_number, ParseError, ParseMode, Solution};
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 enum Rating {
     X,
@@ -38,19 +40,8 @@ pub struct Processor {
     accepted: Vec<Part>,
 }
 
-#[derive(Debug)]
-struct ParseWorkflowError;
-
-impl fmt::Display for ParseWorkflowError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse workflow")
-    }
-}
-
-impl Error for ParseWorkflowError {}
-
 impl FromStr for Rating {
-    type Err = ParseWorkflowError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -58,16 +49,17 @@ impl FromStr for Rating {
             "m" => Ok(Rating::M),
             "a" => Ok(Rating::A),
             "s" => Ok(Rating::S),
-            _ => Err(ParseWorkflowError),
+            _ => Err(ParseError::invalid("rating", s, s)),
         }
     }
 }
 
 impl FromStr for Target {
-    type Err = ParseWorkflowError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "" => Err(ParseError::missing("target", s)),
             "A" => Ok(Target::Accepted),
             "R" => Ok(Target::Rejected),
             w => Ok(Target::Workflow(w.to_string())),
@@ -76,22 +68,24 @@ impl FromStr for Target {
 }
 
 impl FromStr for Rule {
-    type Err = ParseWorkflowError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.contains("<") || s.contains(">") {
             let less_than = s.contains("<");
 
             let mut parts = s.split(if less_than { "<" } else { ">" });
-            let rating = parts.next().unwrap_or_default().parse()?;
+            let rating = next_token(&mut parts, "rating", s)?;
+            let rating = rating
+                .parse()
+                .map_err(|e: ParseError| e.within(s, rating))?;
 
-            let mut parts = parts.next().unwrap_or_default().split(":");
-            let value = parts
-                .next()
-                .unwrap_or_default()
+            let mut parts = next_token(&mut parts, "condition", s)?.split(":");
+            let value = parse_number(next_token(&mut parts, "value", s)?, s)?;
+            let target = next_token(&mut parts, "target", s)?;
+            let target = target
                 .parse()
-                .map_err(|_| ParseWorkflowError)?;
-            let target = parts.next().unwrap_or_default().parse()?;
+                .map_err(|e: ParseError| e.within(s, target))?;
 
             if less_than {
                 Ok(Rule::LessThan(rating, value, target))
@@ -105,16 +99,14 @@ impl FromStr for Rule {
 }
 
 impl FromStr for Workflow {
-    type Err = ParseWorkflowError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.trim_end_matches("}").split("{");
-        let name = parts.next().unwrap_or_default().to_string();
-        let rules = parts
-            .next()
-            .unwrap_or_default()
+        let name = next_token(&mut parts, "workflow name", s)?.to_string();
+        let rules = next_token(&mut parts, "rules", s)?
             .split(",")
-            .map(|p| p.parse())
+            .map(|p| p.parse().map_err(|e: ParseError| e.within(s, p)))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self { name, rules })
@@ -122,19 +114,18 @@ impl FromStr for Workflow {
 }
 
 impl FromStr for Part {
-    type Err = ParseWorkflowError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut part = Self::new();
 
         for r in s.trim_start_matches("{").trim_end_matches("}").split(",") {
             let mut parts = r.split("=");
-            let rating = parts.next().unwrap_or_default().parse()?;
-            let value = parts
-                .next()
-                .unwrap_or_default()
+            let rating = next_token(&mut parts, "rating", s)?;
+            let rating = rating
                 .parse()
-                .map_err(|_| ParseWorkflowError)?;
+                .map_err(|e: ParseError| e.within(s, rating))?;
+            let value = parse_number(next_token(&mut parts, "value", s)?, s)?;
 
             part.ratings.insert(rating, value);
         }
@@ -293,33 +284,47 @@ impl Processor {
             .map(|r| {
                 r.values()
                     .map(|range| range.1 - range.0 + 1)
-                    .fold(1, |acc, s| acc * s)
+                    .product::<u64>()
             })
             .sum()
     }
 }
 
-pub fn build_processor(input: impl Read) -> Result<Processor, Box<dyn Error>> {
-    let mut processor = Processor::new();
+impl Solution for Processor {
+    fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut processor = Processor::new();
 
-    let mut reading_workflows = true;
+        let mut reading_workflows = true;
 
-    for line in io::BufReader::new(input).lines() {
-        let l = line?;
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            let l = line?;
 
-        if l.is_empty() {
-            reading_workflows = false;
-            continue;
-        }
+            if l.is_empty() {
+                reading_workflows = false;
+                continue;
+            }
 
-        if reading_workflows {
-            processor.add_workflow(l.parse()?);
-        } else {
-            processor.process_part(l.parse()?);
+            if reading_workflows {
+                processor.add_workflow(l.parse().map_err(|e: ParseError| e.at_line(index + 1))?);
+            } else {
+                processor.process_part(l.parse().map_err(|e: ParseError| e.at_line(index + 1))?);
+            }
         }
+
+        Ok(processor)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_accepted())
     }
 
-    Ok(processor)
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.sum_accepted_combinations())
+    }
+}
+
+pub fn build_processor(input: impl Read) -> Result<Processor, Box<dyn Error>> {
+    Processor::parse(input)
 }
 
 #[cfg(test)]
@@ -359,4 +364,24 @@ hdj{m>838:A,pv}
 
         Ok(())
     }
+
+    #[test]
+    fn report_invalid_rating() {
+        let list = "in{s<1351:A,z>2:R,A}
+
+{x=787,m=2655,a=1222,s=2876}";
+
+        let error = build_processor(list.as_bytes())
+            .err()
+            .map(|e| e.to_string());
+
+        assert_eq!(
+            Some(String::from(
+                "invalid rating `z` at line 1, column 13
+  in{s<1351:A,z>2:R,A}
+              ^"
+            )),
+            error
+        );
+    }
 }
@@ -1,21 +1,25 @@
 use std::collections::{HashMap, VecDeque};
 use std::error::Error;
-use std::fmt;
+use std::fmt::{self, Display};
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
+use common::{next_token, ParseError, ParseMode, Solution};
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 enum Pulse {
     High,
     Low,
 }
 
+#[derive(Clone)]
 enum ModuleType {
     FlipFlop { state: bool },
     Conjunction { inputs: HashMap<String, Pulse> },
     Broadcast,
 }
 
+#[derive(Clone)]
 struct Module {
     name: String,
     module_type: ModuleType,
@@ -23,25 +27,48 @@ struct Module {
     next_pulse: Option<Pulse>,
 }
 
-struct Network {
+#[derive(Clone)]
+pub struct Network {
     modules: HashMap<String, Module>,
 }
 
 #[derive(Debug)]
-struct ParseModuleError;
+struct MissingModuleError(String);
 
-impl fmt::Display for ParseModuleError {
+impl fmt::Display for MissingModuleError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse module")
+        write!(f, "no module sends pulses to {}", self.0)
     }
 }
 
-impl Error for ParseModuleError {}
+impl Error for MissingModuleError {}
 
 impl FromStr for Module {
-    type Err = ParseModuleError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, ParseMode::Lenient)
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        return a;
+    }
+
+    gcd(b, a % b)
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    if a > b {
+        (a / gcd(a, b)) * b
+    } else {
+        (b / gcd(a, b)) * a
+    }
+}
+
+impl Module {
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
         let module_type = match s.chars().next().unwrap_or_default() {
             '%' => ModuleType::FlipFlop { state: false },
             '&' => ModuleType::Conjunction {
@@ -52,16 +79,19 @@ impl FromStr for Module {
 
         let mut parts = s.split(" -> ");
 
-        let name = parts
-            .next()
-            .unwrap_or_default()
+        let name = next_token(&mut parts, "module name", s)?
             .trim_start_matches("%")
             .trim_start_matches("&")
             .to_string();
 
-        let outputs = parts
-            .next()
-            .unwrap_or_default()
+        if mode == ParseMode::Strict
+            && matches!(module_type, ModuleType::Broadcast)
+            && name != "broadcaster"
+        {
+            return Err(ParseError::invalid("module type", s, &s[..name.len()]));
+        }
+
+        let outputs = next_token(&mut parts, "outputs", s)?
             .split(", ")
             .map(|s| s.to_string())
             .collect();
@@ -73,25 +103,7 @@ impl FromStr for Module {
             next_pulse: None,
         })
     }
-}
-
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        return a;
-    }
-
-    gcd(b, a % b)
-}
-
-fn lcm(a: u64, b: u64) -> u64 {
-    if a > b {
-        (a / gcd(a, b)) * b
-    } else {
-        (b / gcd(a, b)) * a
-    }
-}
 
-impl Module {
     fn receive(&mut self, from: &str, pulse: Pulse) {
         match &mut self.module_type {
             ModuleType::FlipFlop { state } => {
@@ -207,7 +219,22 @@ impl Network {
             self.broadcast(&mut pulse_counts, None);
         }
 
-        pulse_counts.values().fold(1, |acc, c| acc * c)
+        pulse_counts.values().product()
+    }
+
+    fn probes_for(&self, output: &str) -> Result<Vec<&str>, MissingModuleError> {
+        let feed = self
+            .modules
+            .values()
+            .find(|module| module.outputs.iter().any(|o| o == output))
+            .ok_or_else(|| MissingModuleError(output.to_string()))?;
+
+        Ok(self
+            .modules
+            .values()
+            .filter(|module| module.outputs.contains(&feed.name))
+            .map(|module| module.name.as_str())
+            .collect())
     }
 
     fn cycle_length(&mut self, probes: Vec<&str>) -> u64 {
@@ -234,24 +261,34 @@ impl Network {
     }
 }
 
-pub fn count_all_pulses(input: impl Read, presses: u64) -> Result<u64, Box<dyn Error>> {
-    let mut network = Network::new();
+impl Solution for Network {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut network = Network::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            network.add_module(Module::parse_with(&line?, mode).map_err(|e| e.at_line(index + 1))?);
+        }
 
-    for line in io::BufReader::new(input).lines() {
-        network.add_module(line?.parse()?);
+        Ok(network)
     }
 
-    Ok(network.sum_pulses(presses))
-}
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.clone().sum_pulses(1000))
+    }
 
-pub fn count_cycle_length(input: impl Read, probes: Vec<&str>) -> Result<u64, Box<dyn Error>> {
-    let mut network = Network::new();
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        let probes = self.probes_for("rx")?;
 
-    for line in io::BufReader::new(input).lines() {
-        network.add_module(line?.parse()?);
+        Ok(self.clone().cycle_length(probes))
     }
+}
+
+pub fn count_all_pulses(input: impl Read, presses: u64) -> Result<u64, Box<dyn Error>> {
+    Ok(Network::parse(input)?.sum_pulses(presses))
+}
 
-    Ok(network.cycle_length(probes))
+pub fn count_cycle_length(input: impl Read, probes: Vec<&str>) -> Result<u64, Box<dyn Error>> {
+    Ok(Network::parse(input)?.cycle_length(probes))
 }
 
 #[cfg(test)]
@@ -289,4 +326,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn find_probes_feeding_output() -> Result<(), Box<dyn Error>> {
+        let configuration = "broadcaster -> a, b
+%a -> con
+%b -> con
+&con -> rx";
+
+        let network = Network::parse(configuration.as_bytes())?;
+        let mut probes = network.probes_for("rx")?;
+        probes.sort();
+
+        assert_eq!(vec!["a", "b"], probes);
+        assert!(network.probes_for("zz").is_err());
+
+        Ok(())
+    }
 }
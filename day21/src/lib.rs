@@ -1,6 +1,10 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashSet;
 use std::error::Error;
-use std::io::{self, BufRead, Read};
+use std::fmt::{self, Display};
+use std::io::Read;
+
+use common::{FromGlyph, ParseMode, Solution};
+use grid::{Direction, Grid};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -9,18 +13,24 @@ enum Tile {
     Rock,
 }
 
-struct Map {
-    tiles: Vec<Tile>,
-    width: usize,
+pub struct Map {
+    tiles: Grid<Tile>,
+}
+
+impl FromGlyph for Tile {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            'S' => Some(Tile::Start),
+            '.' => Some(Tile::GardenPlot),
+            '#' => Some(Tile::Rock),
+            _ => None,
+        }
+    }
 }
 
 impl From<char> for Tile {
     fn from(value: char) -> Self {
-        match value {
-            'S' => Tile::Start,
-            '.' => Tile::GardenPlot,
-            _ => Tile::Rock,
-        }
+        Tile::from_glyph(value).unwrap_or(Tile::Rock)
     }
 }
 
@@ -30,140 +40,176 @@ impl Tile {
     }
 }
 
-impl Map {
-    fn new() -> Self {
-        Self {
-            tiles: vec![],
-            width: 0,
-        }
-    }
+struct Reachable<'a> {
+    map: &'a Map,
+    wrapping: bool,
+    visited: HashSet<(i64, i64)>,
+    frontier: Vec<(i64, i64)>,
+    plots: [usize; 2],
+    steps: usize,
+}
 
-    fn height(&self) -> usize {
-        self.tiles.len() / self.width
-    }
+impl Iterator for Reachable<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.steps > 0 {
+            let mut frontier = vec![];
+
+            for pos in self.frontier.drain(..) {
+                for next in Direction::ALL
+                    .iter()
+                    .filter_map(|direction| direction.step_signed(pos))
+                {
+                    if self.map.is_reachable(next, self.wrapping) && self.visited.insert(next) {
+                        frontier.push(next);
+                    }
+                }
+            }
 
-    fn add_row(&mut self, mut row: Vec<Tile>) {
-        if self.width == 0 {
-            self.width = row.len();
+            self.frontier = frontier;
         }
 
-        self.tiles.append(&mut row);
-    }
+        self.plots[self.steps % 2] += self.frontier.len();
+        self.steps += 1;
 
-    fn get_tile(&self, x: i64, y: i64) -> Tile {
-        self.tiles[(y.rem_euclid(self.height() as i64) * self.width as i64
-            + (x.rem_euclid(self.width as i64))) as usize]
+        Some(self.plots[(self.steps - 1) % 2])
     }
+}
 
-    fn starting_point(&self) -> Option<(i64, i64)> {
-        self.tiles
-            .iter()
-            .enumerate()
-            .find(|(_, &tile)| tile == Tile::Start)
-            .map(|(index, _)| ((index % self.width) as i64, (index / self.width) as i64))
-    }
+const STABLE_CYCLES: usize = 3;
 
-    fn find_garden_plots2(&self, steps: usize) -> usize {
-        let mut plots = vec![self.starting_point().unwrap_or_default()];
+#[derive(Debug)]
+struct OverflowError;
 
-        for _ in 0..steps {
-            let mut reachable = vec![];
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "number of garden plots does not fit in usize")
+    }
+}
 
-            while let Some(pos) = plots.pop() {
-                if self.get_tile(pos.0, pos.1 - 1).is_reachable() {
-                    reachable.push((pos.0, pos.1 - 1));
-                }
+impl Error for OverflowError {}
 
-                if self.get_tile(pos.0, pos.1 + 1).is_reachable() {
-                    reachable.push((pos.0, pos.1 + 1));
-                }
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        return a;
+    }
 
-                if self.get_tile(pos.0 - 1, pos.1).is_reachable() {
-                    reachable.push((pos.0 - 1, pos.1));
-                }
+    gcd(b, a % b)
+}
 
-                if self.get_tile(pos.0 + 1, pos.1).is_reachable() {
-                    reachable.push((pos.0 + 1, pos.1));
-                }
-            }
+impl Map {
+    fn width(&self) -> usize {
+        self.tiles.width()
+    }
 
-            reachable.sort();
-            reachable.dedup();
+    fn height(&self) -> usize {
+        self.tiles.height()
+    }
 
-            plots = reachable;
+    fn is_reachable(&self, pos: (i64, i64), wrapping: bool) -> bool {
+        if wrapping {
+            self.tiles
+                .get_wrapping(pos)
+                .is_some_and(|tile| tile.is_reachable())
+        } else {
+            self.tiles
+                .get_signed(pos)
+                .is_some_and(|tile| tile.is_reachable())
         }
-
-        plots.len()
     }
 
-    fn find_garden_plots(&self, _steps: usize) -> usize {
-        let mut plots = HashMap::new();
-        let mut next = VecDeque::from([(self.starting_point().unwrap_or_default(), 0)]);
+    fn starting_point(&self) -> Option<(i64, i64)> {
+        self.tiles
+            .position(|&tile| tile == Tile::Start)
+            .map(|(x, y)| (x as i64, y as i64))
+    }
 
-        while let Some((pos, steps)) = next.pop_front() {
-            if plots.contains_key(&pos) {
-                continue;
-            }
+    fn reachable(&self, wrapping: bool) -> Reachable<'_> {
+        let start = self.starting_point();
 
-            if steps > 1000 {
-                break;
-            }
-
-            plots.insert(pos, steps);
+        Reachable {
+            map: self,
+            wrapping,
+            visited: start.into_iter().collect(),
+            frontier: start.into_iter().collect(),
+            plots: [0; 2],
+            steps: 0,
+        }
+    }
 
-            // if pos.1 > self.height() as i64 * -3 && self.get_tile(pos.0, pos.1 - 1).is_reachable() {
-            //     next.push_back(((pos.0, pos.1 - 1), steps + 1));
-            // }
+    fn find_garden_plots(&self, steps: usize) -> usize {
+        self.reachable(false).nth(steps).unwrap_or_default()
+    }
 
-            // if pos.1 < self.height() as i64 * 3 - 1 && self.get_tile(pos.0, pos.1 + 1).is_reachable() {
-            //     next.push_back(((pos.0, pos.1 + 1), steps + 1));
-            // }
+    fn find_infinite_garden_plots(&self, steps: usize) -> Result<usize, OverflowError> {
+        if self.width() == 0 || self.height() == 0 {
+            return Ok(0);
+        }
 
-            // if pos.0 > self.width as i64 * -3 && self.get_tile(pos.0 - 1, pos.1).is_reachable() {
-            //     next.push_back(((pos.0 - 1, pos.1), steps + 1));
-            // }
+        let period = self.width() / gcd(self.width(), self.height()) * self.height();
+        let mut reachable = self.reachable(true);
+        let mut samples = vec![];
 
-            // if pos.0 < self.width as i64 * 3 - 1 && self.get_tile(pos.0 + 1, pos.1).is_reachable() {
-            //     next.push_back(((pos.0 + 1, pos.1), steps + 1));
-            // }
+        while let Some(plots) = reachable.next() {
+            let step = reachable.steps - 1;
 
-            if self.get_tile(pos.0, pos.1 - 1).is_reachable() {
-                next.push_back(((pos.0, pos.1 - 1), steps + 1));
+            if step == steps {
+                return Ok(plots);
             }
 
-            if self.get_tile(pos.0, pos.1 + 1).is_reachable() {
-                next.push_back(((pos.0, pos.1 + 1), steps + 1));
+            if reachable.frontier.is_empty() {
+                return Ok(reachable.plots[steps % 2]);
             }
 
-            if self.get_tile(pos.0 - 1, pos.1).is_reachable() {
-                next.push_back(((pos.0 - 1, pos.1), steps + 1));
+            if step % period != steps % period {
+                continue;
             }
 
-            if self.get_tile(pos.0 + 1, pos.1).is_reachable() {
-                next.push_back(((pos.0 + 1, pos.1), steps + 1));
-            }
-        }
+            samples.push(plots as i128);
 
-        for y in (self.height() as i64 * -3)..(self.height() as i64 * 3) {
-            if y % self.height() as i64 == 0 {
-                println!("-");
-            }
-            for x in (self.width as i64 * -5)..(self.width as i64 * 1) {
-                if x % self.width as i64 == 0 {
-                    print!("|");
-                }
-                let c = if let Some(s) = plots.get(&(x as i64, y as i64)) {
-                    format!("{s:2}")
-                } else {
-                    String::from(" .")
+            let differences = samples
+                .windows(3)
+                .map(|s| s[2] + s[0] - 2 * s[1])
+                .rev()
+                .take(STABLE_CYCLES)
+                .collect::<Vec<_>>();
+
+            if differences.len() == STABLE_CYCLES && differences.windows(2).all(|d| d[0] == d[1]) {
+                let remaining = ((steps - step) / period) as i128;
+                let growth = samples[samples.len() - 1] - samples[samples.len() - 2];
+
+                let total = || {
+                    let triangle = remaining.checked_mul(remaining + 1)? / 2;
+
+                    (plots as i128)
+                        .checked_add(remaining.checked_mul(growth)?)?
+                        .checked_add(triangle.checked_mul(differences[0])?)
                 };
-                print!("{c} ");
-            }
 
-            println!();
+                return total()
+                    .and_then(|total| usize::try_from(total).ok())
+                    .ok_or(OverflowError);
+            }
         }
 
-        0
+        Ok(0)
+    }
+}
+
+impl Solution for Map {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            tiles: Grid::parse_with(input, mode)?,
+        })
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.find_garden_plots(64))
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.find_infinite_garden_plots(26_501_365)?)
     }
 }
 
@@ -171,14 +217,14 @@ pub fn count_reachable_garden_plots(
     input: impl Read,
     steps: usize,
 ) -> Result<usize, Box<dyn Error>> {
-    let mut map = Map::new();
-
-    for line in io::BufReader::new(input).lines() {
-        let row = line?.chars().map(|c| c.into()).collect();
-        map.add_row(row);
-    }
+    Ok(Map::parse(input)?.find_garden_plots(steps))
+}
 
-    Ok(map.find_garden_plots(steps))
+pub fn count_infinite_garden_plots(
+    input: impl Read,
+    steps: usize,
+) -> Result<usize, Box<dyn Error>> {
+    Ok(Map::parse(input)?.find_infinite_garden_plots(steps)?)
 }
 
 #[cfg(test)]
@@ -204,31 +250,39 @@ mod tests {
         Ok(())
     }
 
-    // #[test]
-    // fn reachable_garden_plots_in_10_steps() -> Result<(), Box<dyn Error>> {
-    //     assert_eq!(50, count_reachable_garden_plots(MAP.as_bytes(), 10)?);
-
-    //     Ok(())
-    // }
+    #[test]
+    fn reachable_infinite_garden_plots() -> Result<(), Box<dyn Error>> {
+        for (steps, plots) in [
+            (6, 16),
+            (10, 50),
+            (50, 1594),
+            (100, 6536),
+            (500, 167004),
+            (1000, 668697),
+            (5000, 16733044),
+        ] {
+            assert_eq!(plots, count_infinite_garden_plots(MAP.as_bytes(), steps)?);
+        }
 
-    // #[test]
-    // fn reachable_garden_plots_in_50_steps() -> Result<(), Box<dyn Error>> {
-    //     assert_eq!(1594, count_reachable_garden_plots(MAP.as_bytes(), 50)?);
+        Ok(())
+    }
 
-    //     Ok(())
-    // }
+    #[test]
+    fn empty_map_has_no_plots() -> Result<(), Box<dyn Error>> {
+        assert_eq!(0, count_infinite_garden_plots("".as_bytes(), 100)?);
+        assert_eq!(0, count_reachable_garden_plots("".as_bytes(), 100)?);
 
-    // #[test]
-    // fn reachable_garden_plots_in_100_steps() -> Result<(), Box<dyn Error>> {
-    //     assert_eq!(6536, count_reachable_garden_plots(MAP.as_bytes(), 100)?);
+        Ok(())
+    }
 
-    //     Ok(())
-    // }
+    #[test]
+    fn enclosed_map_stops_growing() -> Result<(), Box<dyn Error>> {
+        let map = "#####\n#.S.#\n#####";
 
-    // #[test]
-    // fn reachable_garden_plots_in_500_steps() -> Result<(), Box<dyn Error>> {
-    //     assert_eq!(6536, count_reachable_garden_plots(MAP.as_bytes(), 500)?);
+        assert_eq!(1, count_infinite_garden_plots(map.as_bytes(), 3000)?);
+        assert_eq!(2, count_infinite_garden_plots(map.as_bytes(), 3001)?);
+        assert_eq!(1, count_reachable_garden_plots(map.as_bytes(), 3000)?);
 
-    //     Ok(())
-    // }
+        Ok(())
+    }
 }
@@ -9,5 +9,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         count_reachable_garden_plots(File::open("input.txt")?, 64)?
     );
 
+    println!(
+        "Garden plots reachable in 26501365 steps: {}",
+        count_infinite_garden_plots(File::open("input.txt")?, 26_501_365)?
+    );
+
     Ok(())
 }
@@ -1,9 +1,11 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
-use std::fmt;
+use std::fmt::Display;
 use std::io::{self, BufRead, Read};
 use std::str::FromStr;
 
+use common::{next_token, parse_number, ParseError, ParseMode, Solution};
+
 struct Vertex {
     x: u64,
     y: u64,
@@ -21,53 +23,30 @@ pub struct Snapshot {
     supporteds: Vec<Vec<usize>>,
 }
 
-#[derive(Debug)]
-struct ParseBrickError;
-
-impl fmt::Display for ParseBrickError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse brick")
-    }
-}
-
-impl Error for ParseBrickError {}
-
 impl FromStr for Vertex {
-    type Err = ParseBrickError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split(",");
 
-        let x = parts
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .map_err(|_| ParseBrickError)?;
-
-        let y = parts
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .map_err(|_| ParseBrickError)?;
-
-        let z = parts
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .map_err(|_| ParseBrickError)?;
+        let x = parse_number(next_token(&mut parts, "x coordinate", s)?, s)?;
+        let y = parse_number(next_token(&mut parts, "y coordinate", s)?, s)?;
+        let z = parse_number(next_token(&mut parts, "z coordinate", s)?, s)?;
 
         Ok(Self { x, y, z })
     }
 }
 
 impl FromStr for Brick {
-    type Err = ParseBrickError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split("~");
 
-        let from = parts.next().unwrap_or_default().parse::<Vertex>()?;
-        let to = parts.next().unwrap_or_default().parse::<Vertex>()?;
+        let from = next_token(&mut parts, "start vertex", s)?;
+        let from = from.parse::<Vertex>().map_err(|e| e.within(s, from))?;
+        let to = next_token(&mut parts, "end vertex", s)?;
+        let to = to.parse::<Vertex>().map_err(|e| e.within(s, to))?;
 
         let (from, to) = if to.z > from.z {
             (from, to)
@@ -96,7 +75,7 @@ impl Snapshot {
     }
 
     fn find_supports(&mut self) {
-        self.bricks.sort_by(|a, b| a.from.z.cmp(&b.from.z));
+        self.bricks.sort_by_key(|brick| brick.from.z);
         self.supporteds = vec![vec![]; self.bricks.len()];
 
         let mut tops = HashMap::new();
@@ -199,16 +178,34 @@ impl Snapshot {
     }
 }
 
-pub fn build_snapshot(input: impl Read) -> Result<Snapshot, Box<dyn Error>> {
-    let mut snapshot = Snapshot::new();
+impl Solution for Snapshot {
+    fn parse_with(input: impl Read, _mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut snapshot = Snapshot::new();
 
-    for line in io::BufReader::new(input).lines() {
-        snapshot.add_brick(line?.parse()?);
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            snapshot.add_brick(
+                line?
+                    .parse()
+                    .map_err(|e: ParseError| e.at_line(index + 1))?,
+            );
+        }
+
+        snapshot.find_supports();
+
+        Ok(snapshot)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.safe_to_disintegrate())
     }
 
-    snapshot.find_supports();
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.would_fall())
+    }
+}
 
-    Ok(snapshot)
+pub fn build_snapshot(input: impl Read) -> Result<Snapshot, Box<dyn Error>> {
+    Snapshot::parse(input)
 }
 
 #[cfg(test)]
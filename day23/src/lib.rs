@@ -1,14 +1,10 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::error::Error;
-use std::io::{self, BufRead, Read};
+use std::fmt::{self, Display};
+use std::io::Read;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use common::{FromGlyph, ParseMode, Solution};
+use grid::{Direction, Grid};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -17,163 +13,242 @@ enum Tile {
     Slope(Direction),
 }
 
-struct Map {
-    tiles: Vec<Tile>,
-    width: usize,
+pub struct Map {
+    tiles: Grid<Tile>,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
-        match value {
-            '.' => Tile::Path,
-            '^' => Tile::Slope(Direction::Up),
-            'v' => Tile::Slope(Direction::Down),
-            '<' => Tile::Slope(Direction::Left),
-            '>' => Tile::Slope(Direction::Right),
-            _ => Tile::Forest,
+impl FromGlyph for Tile {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '.' => Some(Tile::Path),
+            '^' => Some(Tile::Slope(Direction::Up)),
+            'v' => Some(Tile::Slope(Direction::Down)),
+            '<' => Some(Tile::Slope(Direction::Left)),
+            '>' => Some(Tile::Slope(Direction::Right)),
+            '#' => Some(Tile::Forest),
+            _ => None,
         }
     }
 }
 
-impl Direction {
-    fn opposite(&self) -> Self {
-        match *self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
-        }
+impl From<char> for Tile {
+    fn from(value: char) -> Self {
+        Tile::from_glyph(value).unwrap_or(Tile::Forest)
     }
+}
 
-    fn step(&self, pos: (usize, usize)) -> (usize, usize) {
-        match *self {
-            Direction::Up => (pos.0, pos.1 - 1),
-            Direction::Down => (pos.0, pos.1 + 1),
-            Direction::Left => (pos.0 - 1, pos.1),
-            Direction::Right => (pos.0 + 1, pos.1),
+#[derive(Clone, Copy)]
+struct Trail {
+    to: usize,
+    steps: usize,
+}
+
+struct Junctions {
+    trails: Vec<Vec<Trail>>,
+    start: usize,
+    end: usize,
+    exit: Option<usize>,
+}
+
+#[derive(Debug)]
+enum HikeError {
+    MissingEntrance,
+    MissingExit,
+    TooManyJunctions(usize),
+}
+
+impl fmt::Display for HikeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HikeError::MissingEntrance => write!(f, "no path tile in the first row"),
+            HikeError::MissingExit => write!(f, "no path tile in the last row"),
+            HikeError::TooManyJunctions(count) => write!(
+                f,
+                "{count} junctions do not fit in a {}-bit visited set",
+                u64::BITS
+            ),
         }
     }
 }
 
+impl Error for HikeError {}
+
 impl Tile {
-    fn is_reachable_from(&self, direction: Direction) -> bool {
+    fn is_reachable_from(&self, direction: Direction, ignore_slopes: bool) -> bool {
         match *self {
             Tile::Path => true,
             Tile::Forest => false,
+            Tile::Slope(_) if ignore_slopes => true,
             Tile::Slope(slope_dir) => slope_dir != direction.opposite(),
         }
     }
 }
 
-impl Map {
-    fn new() -> Self {
-        Self {
-            tiles: vec![],
-            width: 0,
+impl Junctions {
+    fn longest_from(&self, node: usize, visited: u64) -> Option<usize> {
+        if node == self.end {
+            return Some(0);
         }
-    }
 
-    fn add_row(&mut self, mut row: Vec<Tile>) {
-        if self.width == 0 {
-            self.width = row.len();
+        if Some(node) == self.exit {
+            return self.trails[node]
+                .iter()
+                .find(|trail| trail.to == self.end)
+                .map(|trail| trail.steps);
         }
 
-        self.tiles.append(&mut row);
+        self.trails[node]
+            .iter()
+            .filter(|trail| visited & (1 << trail.to) == 0)
+            .filter_map(|trail| {
+                self.longest_from(trail.to, visited | (1 << trail.to))
+                    .map(|steps| steps + trail.steps)
+            })
+            .max()
     }
 
+    fn longest_hike(&self) -> usize {
+        self.longest_from(self.start, 1 << self.start)
+            .unwrap_or_default()
+    }
+}
+
+impl Map {
     fn height(&self) -> usize {
-        self.tiles.len() / self.width
+        self.tiles.height()
     }
 
     fn get_tile(&self, pos: (usize, usize)) -> Tile {
-        self.tiles[pos.1 * self.width + pos.0]
+        self.tiles[pos]
     }
 
-    fn longest_hike_from(&self, mut pos: (usize, usize), mut direction: Direction, mut visited: HashSet<(usize, usize)>, root: bool) -> usize {
-        // println!("{:?}", pos);
-        let mut steps = 1;
-
-        if !visited.insert(pos) {
-            return 0;
-        }
-
-        loop {
-
+    fn path_in_row(&self, y: usize) -> Option<(usize, usize)> {
+        self.tiles
+            .row(y)
+            .position(|&tile| tile == Tile::Path)
+            .map(|x| (x, y))
+    }
 
-            pos = direction.step(pos);
+    fn is_junction(&self, pos: (usize, usize)) -> bool {
+        self.get_tile(pos) != Tile::Forest
+            && self
+                .tiles
+                .neighbors4(pos)
+                .filter(|&next| self.get_tile(next) != Tile::Forest)
+                .count()
+                > 2
+    }
 
-            if !visited.insert(pos) {
-                // println!("{:?} visited", pos);
-                return 0;
-                // break;
-            } else {
-                // println!("{:?} added", pos);
-            }
+    fn step(
+        &self,
+        pos: (usize, usize),
+        direction: Direction,
+        ignore_slopes: bool,
+    ) -> Option<(usize, usize)> {
+        self.tiles.step(pos, direction).filter(|&next| {
+            self.get_tile(next)
+                .is_reachable_from(direction, ignore_slopes)
+        })
+    }
 
-            if pos.1 == self.height() - 1 {
-                break;
-            }
+    fn follow_trail(
+        &self,
+        mut pos: (usize, usize),
+        mut direction: Direction,
+        ignore_slopes: bool,
+        junctions: &HashMap<(usize, usize), usize>,
+    ) -> Option<Trail> {
+        let mut steps = 0;
 
+        loop {
+            pos = self.step(pos, direction, ignore_slopes)?;
             steps += 1;
 
-            let valid_directions = [Direction::Up, Direction::Down, Direction::Left, Direction::Right].iter().filter(|&&dir| {
-                dir != direction.opposite() && self.get_tile(dir.step(pos)).is_reachable_from(dir)
-            }).cloned().collect::<Vec<_>>();
-
-            match valid_directions.len() {
-                0 => break,
-                1 => direction = valid_directions[0],
-                _ => {
-                    // let mut i = 0;
-                    // let mut v = HashSet::new();
-                    let res = valid_directions.iter().map(|&dir| {
-                    // println!("check {:?} {steps}", dir.step(pos));
-                    let s = self.longest_hike_from(dir.step(pos), dir, visited.clone(), false);
-                    // println!("checked {:?} {} {steps}", dir.step(pos), s);
-                    // if s > i {
-                    //     i = s;
-                    //     v = vis.clone();
-                    // }
-                    s + steps
-                }).max().unwrap_or_default();
-                // visited.extend(v.iter());
-                // return (res, visited);
-                steps = res;
-                break;
-
-
-                // direction = *valid_directions.iter().last().unwrap();
-            }
+            if let Some(&to) = junctions.get(&pos) {
+                return Some(Trail { to, steps });
             }
-        }
 
-        // if root {
-        //     for y in 0..self.height() {
-        //         for x in 0..self.width {
-        //             let c = if visited.contains(&(x, y)) { "O" } else if self.get_tile((x, y)) == Tile::Forest { "#" } else { "." };
-        //             print!("{c}");
-        //         }
+            direction = Direction::ALL.into_iter().find(|&next| {
+                next != direction.opposite() && self.step(pos, next, ignore_slopes).is_some()
+            })?;
+        }
+    }
 
-        //         println!();
-        //     }
-        // }
+    fn junctions(&self, ignore_slopes: bool) -> Result<Junctions, HikeError> {
+        let start = self.path_in_row(0).ok_or(HikeError::MissingEntrance)?;
+        let end = self
+            .height()
+            .checked_sub(1)
+            .and_then(|y| self.path_in_row(y))
+            .ok_or(HikeError::MissingExit)?;
+
+        let positions = [start, end]
+            .into_iter()
+            .chain(
+                self.tiles
+                    .iter()
+                    .map(|(pos, _)| pos)
+                    .filter(|&pos| self.is_junction(pos)),
+            )
+            .collect::<Vec<_>>();
+
+        if positions.len() > u64::BITS as usize {
+            return Err(HikeError::TooManyJunctions(positions.len()));
+        }
 
-        steps
+        let indices = positions
+            .iter()
+            .enumerate()
+            .map(|(index, &pos)| (pos, index))
+            .collect::<HashMap<_, _>>();
+
+        let trails: Vec<Vec<Trail>> = positions
+            .iter()
+            .map(|&pos| {
+                Direction::ALL
+                    .into_iter()
+                    .filter_map(|direction| {
+                        self.follow_trail(pos, direction, ignore_slopes, &indices)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut exits =
+            (0..trails.len()).filter(|&node| trails[node].iter().any(|trail| trail.to == 1));
+        let exit = exits.next().filter(|_| exits.next().is_none());
+
+        Ok(Junctions {
+            trails,
+            start: 0,
+            end: 1,
+            exit,
+        })
     }
 
-    fn longest_hike(&self) -> usize {
-        self.longest_hike_from((1, 0), Direction::Down, HashSet::new(), true)
+    fn longest_hike(&self, ignore_slopes: bool) -> Result<usize, HikeError> {
+        Ok(self.junctions(ignore_slopes)?.longest_hike())
     }
 }
 
-pub fn longest_hike_steps(input: impl Read) -> Result<usize, Box<dyn Error>> {
-    let mut map = Map::new();
+impl Solution for Map {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            tiles: Grid::parse_with(input, mode)?,
+        })
+    }
 
-    for line in io::BufReader::new(input).lines() {
-        map.add_row(line?.chars().map(|c| c.into()).collect());
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.longest_hike(false)?)
     }
 
-    Ok(map.longest_hike())
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.longest_hike(true)?)
+    }
+}
+
+pub fn longest_hike_steps(input: impl Read, ignore_slopes: bool) -> Result<usize, Box<dyn Error>> {
+    Ok(Map::parse(input)?.longest_hike(ignore_slopes)?)
 }
 
 #[cfg(test)]
@@ -206,8 +281,31 @@ mod tests {
 
     #[test]
     fn find_longest_hike() -> Result<(), Box<dyn Error>> {
-        assert_eq!(94, longest_hike_steps(MAP.as_bytes())?);
+        assert_eq!(94, longest_hike_steps(MAP.as_bytes(), false)?);
 
         Ok(())
     }
+
+    #[test]
+    fn find_longest_hike_ignoring_slopes() -> Result<(), Box<dyn Error>> {
+        assert_eq!(154, longest_hike_steps(MAP.as_bytes(), true)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_missing_entrance_or_exit() {
+        assert_eq!(
+            Some(String::from("no path tile in the first row")),
+            longest_hike_steps("".as_bytes(), false)
+                .err()
+                .map(|e| e.to_string())
+        );
+        assert_eq!(
+            Some(String::from("no path tile in the last row")),
+            longest_hike_steps("#.#\n#.#\n###".as_bytes(), false)
+                .err()
+                .map(|e| e.to_string())
+        );
+    }
 }
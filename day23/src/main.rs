@@ -6,7 +6,12 @@ use day23::*;
 fn main() -> Result<(), Box<dyn Error>> {
     println!(
         "Steps of longest hike: {}",
-        longest_hike_steps(File::open("input.txt")?)?
+        longest_hike_steps(File::open("input.txt")?, false)?
+    );
+
+    println!(
+        "Steps of longest hike ignoring slopes: {}",
+        longest_hike_steps(File::open("input.txt")?, true)?
     );
 
     Ok(())
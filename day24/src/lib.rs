@@ -0,0 +1,385 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Read};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use common::{next_token, parse_number_with, ParseError, ParseMode, Solution};
+
+pub const TEST_AREA: RangeInclusive<i64> = 200_000_000_000_000..=400_000_000_000_000;
+
+type Vector = [i128; 3];
+
+#[derive(Clone, Copy)]
+struct Hailstone {
+    position: Vector,
+    velocity: Vector,
+}
+
+pub struct Hailstorm {
+    hailstones: Vec<Hailstone>,
+}
+
+enum Candidate {
+    Degenerate,
+    Impossible,
+    Rock(Hailstone),
+}
+
+#[derive(Debug)]
+struct NoTrajectoryError;
+
+impl fmt::Display for NoTrajectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no integer rock trajectory hits every hailstone")
+    }
+}
+
+impl Error for NoTrajectoryError {}
+
+fn sub(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vector, factor: i128) -> Option<Vector> {
+    Some([
+        a[0].checked_mul(factor)?,
+        a[1].checked_mul(factor)?,
+        a[2].checked_mul(factor)?,
+    ])
+}
+
+fn cross(a: Vector, b: Vector) -> Option<Vector> {
+    let component =
+        |i: usize, j: usize| a[i].checked_mul(b[j])?.checked_sub(a[j].checked_mul(b[i])?);
+
+    Some([component(1, 2)?, component(2, 0)?, component(0, 1)?])
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        return a.abs();
+    }
+
+    gcd(b, a % b)
+}
+
+fn parse_vector(s: &str, mode: ParseMode) -> Result<Vector, ParseError> {
+    let mut parts = s.split(',').map(str::trim);
+    let mut vector = [0; 3];
+
+    for (component, what) in vector.iter_mut().zip(["x", "y", "z"]) {
+        *component = parse_number_with::<i64>(next_token(&mut parts, what, s)?, s, mode)?.into();
+    }
+
+    Ok(vector)
+}
+
+impl FromStr for Hailstone {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, ParseMode::Lenient)
+    }
+}
+
+impl Hailstone {
+    fn parse_with(s: &str, mode: ParseMode) -> Result<Self, ParseError> {
+        let mut parts = s.split('@');
+
+        let position = next_token(&mut parts, "position", s)?;
+        let position = parse_vector(position, mode).map_err(|e| e.within(s, position))?;
+        let velocity = next_token(&mut parts, "velocity", s)?;
+        let velocity = parse_vector(velocity, mode).map_err(|e| e.within(s, velocity))?;
+
+        Ok(Self { position, velocity })
+    }
+
+    fn relative_to(&self, other: &Hailstone) -> Self {
+        Self {
+            position: sub(self.position, other.position),
+            velocity: sub(self.velocity, other.velocity),
+        }
+    }
+
+    fn crosses_within(&self, other: &Hailstone, area: &RangeInclusive<i64>) -> bool {
+        let [px, py, _] = self.position;
+        let [vx, vy, _] = self.velocity;
+        let [ux, uy, _] = other.velocity;
+        let [dx, dy, _] = sub(other.position, self.position);
+
+        let det = vx * uy - vy * ux;
+
+        if det == 0 {
+            return false;
+        }
+
+        let (det, t, s) = (
+            det.abs(),
+            det.signum() * (dx * uy - dy * ux),
+            det.signum() * (dx * vy - dy * vx),
+        );
+
+        if t < 0 || s < 0 {
+            return false;
+        }
+
+        let bounds = (*area.start() as i128 * det)..=(*area.end() as i128 * det);
+
+        bounds.contains(&(px * det + vx * t)) && bounds.contains(&(py * det + vy * t))
+    }
+
+    fn time_to_line(&self, direction: Vector) -> Option<i128> {
+        let offset = cross(self.position, direction)?;
+        let rate = cross(self.velocity, direction)?;
+
+        let (offset, rate) = offset.into_iter().zip(rate).find(|&(_, rate)| rate != 0)?;
+
+        (offset % rate == 0).then(|| -offset / rate)
+    }
+
+    fn position_at(&self, time: i128) -> Option<Vector> {
+        let moved = scale(self.velocity, time)?;
+
+        Some(add(self.position, moved))
+    }
+
+    fn is_hit_by(&self, rock: &Hailstone) -> bool {
+        let relative = rock.relative_to(self);
+
+        let Some((offset, rate)) = relative
+            .position
+            .into_iter()
+            .zip(relative.velocity)
+            .find(|&(_, rate)| rate != 0)
+        else {
+            return relative.position == [0; 3];
+        };
+
+        if offset % rate != 0 || -offset / rate < 0 {
+            return false;
+        }
+
+        relative.position_at(-offset / rate) == Some([0; 3])
+    }
+}
+
+impl Hailstorm {
+    fn new() -> Self {
+        Self { hailstones: vec![] }
+    }
+
+    fn add_hailstone(&mut self, hailstone: Hailstone) {
+        self.hailstones.push(hailstone);
+    }
+
+    pub fn count_crossings(&self, area: &RangeInclusive<i64>) -> usize {
+        self.hailstones
+            .iter()
+            .enumerate()
+            .flat_map(|(index, a)| self.hailstones[index + 1..].iter().map(move |b| (a, b)))
+            .filter(|(a, b)| a.crosses_within(b, area))
+            .count()
+    }
+
+    fn candidate(origin: &Hailstone, first: &Hailstone, second: &Hailstone) -> Candidate {
+        let (first, second) = (first.relative_to(origin), second.relative_to(origin));
+
+        // In the frame of the origin hailstone the rock passes through the origin,
+        // so its path lies in the plane spanned by each other hailstone's path.
+        let Some(direction) = cross(first.position, first.velocity)
+            .zip(cross(second.position, second.velocity))
+            .and_then(|(a, b)| cross(a, b))
+        else {
+            return Candidate::Degenerate;
+        };
+
+        let divisor = direction.into_iter().fold(0, gcd);
+
+        if divisor == 0 {
+            return Candidate::Degenerate;
+        }
+
+        let direction = direction.map(|component| component / divisor);
+
+        let Some((t1, t2)) = first
+            .time_to_line(direction)
+            .zip(second.time_to_line(direction))
+        else {
+            return Candidate::Degenerate;
+        };
+
+        let Some((hit1, hit2)) = first.position_at(t1).zip(second.position_at(t2)) else {
+            return Candidate::Degenerate;
+        };
+
+        let elapsed = t2 - t1;
+        let travelled = sub(hit2, hit1);
+
+        if elapsed == 0 {
+            return Candidate::Degenerate;
+        }
+
+        if travelled.iter().any(|&c| c % elapsed != 0) {
+            return Candidate::Impossible;
+        }
+
+        let velocity = travelled.map(|component| component / elapsed);
+
+        let Some(position) = scale(velocity, t1).map(|moved| sub(hit1, moved)) else {
+            return Candidate::Impossible;
+        };
+
+        Candidate::Rock(Hailstone {
+            position: add(position, origin.position),
+            velocity: add(velocity, origin.velocity),
+        })
+    }
+
+    fn find_rock(&self) -> Option<Hailstone> {
+        let stones = &self.hailstones;
+
+        for (index, origin) in stones.iter().enumerate() {
+            let others = stones[..index].iter().chain(&stones[index + 1..]);
+
+            for (offset, first) in others.clone().enumerate() {
+                for second in others.clone().skip(offset + 1) {
+                    match Self::candidate(origin, first, second) {
+                        Candidate::Degenerate => continue,
+                        Candidate::Impossible => return None,
+                        Candidate::Rock(rock) => {
+                            return stones
+                                .iter()
+                                .all(|hailstone| hailstone.is_hit_by(&rock))
+                                .then_some(rock)
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn rock_position_sum(&self) -> Result<i128, NoTrajectoryError> {
+        self.find_rock()
+            .map(|rock| rock.position.iter().sum())
+            .ok_or(NoTrajectoryError)
+    }
+}
+
+impl Solution for Hailstorm {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut hailstorm = Hailstorm::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            hailstorm.add_hailstone(
+                Hailstone::parse_with(&line?, mode).map_err(|e| e.at_line(index + 1))?,
+            );
+        }
+
+        Ok(hailstorm)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.count_crossings(&TEST_AREA))
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.rock_position_sum()?)
+    }
+}
+
+pub fn count_crossings(
+    input: impl Read,
+    area: RangeInclusive<i64>,
+) -> Result<usize, Box<dyn Error>> {
+    Ok(Hailstorm::parse(input)?.count_crossings(&area))
+}
+
+pub fn sum_rock_position(input: impl Read) -> Result<i128, Box<dyn Error>> {
+    Ok(Hailstorm::parse(input)?.rock_position_sum()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAILSTONES: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn count_crossings_in_test_area() -> Result<(), Box<dyn Error>> {
+        assert_eq!(2, count_crossings(HAILSTONES.as_bytes(), 7..=27)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sum_rock_position_hitting_every_hailstone() -> Result<(), Box<dyn Error>> {
+        assert_eq!(47, sum_rock_position(HAILSTONES.as_bytes())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn skip_degenerate_hailstone_pairs() -> Result<(), Box<dyn Error>> {
+        let hailstones = "19, 13, 30 @ -2,  1, -2
+22, 13, 18 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+        assert_eq!(47, sum_rock_position(hailstones.as_bytes())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hit_only_at_a_future_integer_time() {
+        let rock = Hailstone {
+            position: [0; 3],
+            velocity: [1; 3],
+        };
+        let stone = |position, velocity| Hailstone {
+            position: [position; 3],
+            velocity: [velocity; 3],
+        };
+
+        assert!(stone(-2, 2).is_hit_by(&rock));
+        assert!(stone(0, 5).is_hit_by(&rock));
+        assert!(!stone(1, 1).is_hit_by(&rock));
+        assert!(stone(0, 1).is_hit_by(&rock));
+        assert!(!stone(2, 2).is_hit_by(&rock));
+        assert!(!stone(-1, 3).is_hit_by(&rock));
+        assert!(!Hailstone {
+            position: [1, 0, 0],
+            velocity: [1; 3],
+        }
+        .is_hit_by(&rock));
+    }
+
+    #[test]
+    fn reject_missing_velocity() {
+        let error = Hailstorm::parse("19, 13, 30 @ -2,  1".as_bytes())
+            .err()
+            .map(|e| e.to_string());
+
+        assert_eq!(
+            Some(String::from(
+                "missing z at line 1, column 20
+  19, 13, 30 @ -2,  1
+                     ^"
+            )),
+            error
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use std::error::Error;
+use std::fs::File;
+
+use day24::*;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    println!(
+        "Hailstone paths crossing inside the test area: {}",
+        count_crossings(File::open("input.txt")?, TEST_AREA)?
+    );
+
+    println!(
+        "Sum of the coordinates of the rock's initial position: {}",
+        sum_rock_position(File::open("input.txt")?)?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Read};
+
+use common::{next_token, ParseError, ParseMode, Solution, UnsolvedError};
+
+const CUT_WIRES: usize = 3;
+
+pub struct Apparatus {
+    names: HashMap<String, usize>,
+    wires: Vec<Vec<usize>>,
+}
+
+#[derive(Debug)]
+struct NoCutError(usize);
+
+impl fmt::Display for NoCutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no cut of {} wires splits the components in two", self.0)
+    }
+}
+
+impl Error for NoCutError {}
+
+impl Apparatus {
+    fn new() -> Self {
+        Self {
+            names: HashMap::new(),
+            wires: vec![],
+        }
+    }
+
+    fn component(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.names.get(name) {
+            return index;
+        }
+
+        self.names.insert(name.to_string(), self.wires.len());
+        self.wires.push(vec![]);
+
+        self.wires.len() - 1
+    }
+
+    fn parse_line(&mut self, line: &str, mode: ParseMode) -> Result<(), ParseError> {
+        let mut parts = line.split(": ");
+
+        let name = next_token(&mut parts, "component name", line)?;
+        let connected = next_token(&mut parts, "connected components", line)?;
+
+        if mode == ParseMode::Strict && connected.trim().is_empty() {
+            return Err(ParseError::missing("connected components", line));
+        }
+
+        let from = self.component(name);
+
+        for other in connected.split_ascii_whitespace() {
+            let to = self.component(other);
+
+            self.wires[from].push(to);
+            self.wires[to].push(from);
+        }
+
+        Ok(())
+    }
+
+    fn reachable_side(&self, source: usize, sink: usize, limit: usize) -> Option<Vec<bool>> {
+        let mut flow = HashMap::new();
+
+        for paths in 0..=limit {
+            let mut previous = vec![None; self.wires.len()];
+            let mut queue = VecDeque::from([source]);
+
+            previous[source] = Some(source);
+
+            while let Some(component) = queue.pop_front() {
+                if component == sink {
+                    break;
+                }
+
+                for &next in &self.wires[component] {
+                    if previous[next].is_none()
+                        && flow.get(&(component, next)).copied().unwrap_or(0) < 1
+                    {
+                        previous[next] = Some(component);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            if previous[sink].is_none() {
+                return (paths == limit).then(|| previous.iter().map(Option::is_some).collect());
+            }
+
+            let mut component = sink;
+
+            while let Some(from) = previous[component].filter(|&from| from != component) {
+                *flow.entry((from, component)).or_insert(0) += 1;
+                *flow.entry((component, from)).or_insert(0) -= 1;
+                component = from;
+            }
+        }
+
+        None
+    }
+
+    pub fn split_groups(&self, wires: usize) -> Option<(usize, usize)> {
+        (1..self.wires.len()).find_map(|sink| {
+            let side = self.reachable_side(0, sink, wires)?;
+            let size = side.iter().filter(|&&reached| reached).count();
+
+            Some((size, self.wires.len() - size))
+        })
+    }
+
+    fn group_product(&self, wires: usize) -> Result<usize, NoCutError> {
+        self.split_groups(wires)
+            .map(|(a, b)| a * b)
+            .ok_or(NoCutError(wires))
+    }
+}
+
+impl Solution for Apparatus {
+    fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut apparatus = Apparatus::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            apparatus
+                .parse_line(&line?, mode)
+                .map_err(|e| e.at_line(index + 1))?;
+        }
+
+        Ok(apparatus)
+    }
+
+    fn part1(&self) -> Result<impl Display, Box<dyn Error>> {
+        Ok(self.group_product(CUT_WIRES)?)
+    }
+
+    fn part2(&self) -> Result<impl Display, Box<dyn Error>> {
+        Err::<usize, _>(Box::new(UnsolvedError))
+    }
+}
+
+pub fn multiply_group_sizes(input: impl Read, wires: usize) -> Result<usize, Box<dyn Error>> {
+    Ok(Apparatus::parse(input)?.group_product(wires)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIRING: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn multiply_group_sizes_after_cut() -> Result<(), Box<dyn Error>> {
+        assert_eq!(54, multiply_group_sizes(WIRING.as_bytes(), 3)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_cut_too_small() {
+        assert!(multiply_group_sizes(WIRING.as_bytes(), 2).is_err());
+    }
+
+    #[test]
+    fn require_exact_cut_size() -> Result<(), Box<dyn Error>> {
+        let bridged = "a1: a2 a3 a4 a5 a6 b1
+a2: a3 a4 a5 a6
+a3: a4 a5 a6
+a4: a5 a6
+a5: a6
+b1: b2 b3 b4 b5 b6
+b2: b3 b4 b5 b6
+b3: b4 b5 b6
+b4: b5 b6
+b5: b6";
+
+        assert!(multiply_group_sizes(bridged.as_bytes(), 3).is_err());
+        assert_eq!(36, multiply_group_sizes(bridged.as_bytes(), 1)?);
+
+        Ok(())
+    }
+}
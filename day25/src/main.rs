@@ -0,0 +1,13 @@
+use std::error::Error;
+use std::fs::File;
+
+use day25::*;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    println!(
+        "Product of the group sizes after disconnecting three wires: {}",
+        multiply_group_sizes(File::open("input.txt")?, 3)?
+    );
+
+    Ok(())
+}
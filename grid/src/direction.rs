@@ -0,0 +1,126 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_left(&self) -> Self {
+        match *self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        match *self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match *self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        *self == Direction::Up || *self == Direction::Down
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        !self.is_vertical()
+    }
+
+    pub fn offset(&self) -> (i64, i64) {
+        match *self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    pub fn step(&self, pos: (usize, usize)) -> Option<(usize, usize)> {
+        self.step_by(pos, 1)
+    }
+
+    pub fn step_by(&self, (x, y): (usize, usize), by: usize) -> Option<(usize, usize)> {
+        match *self {
+            Direction::Up => Some((x, y.checked_sub(by)?)),
+            Direction::Right => Some((x.checked_add(by)?, y)),
+            Direction::Down => Some((x, y.checked_add(by)?)),
+            Direction::Left => Some((x.checked_sub(by)?, y)),
+        }
+    }
+
+    pub fn step_signed(&self, pos: (i64, i64)) -> Option<(i64, i64)> {
+        self.step_signed_by(pos, 1)
+    }
+
+    pub fn step_signed_by(&self, (x, y): (i64, i64), by: i64) -> Option<(i64, i64)> {
+        let (dx, dy) = self.offset();
+
+        Some((
+            x.checked_add(dx.checked_mul(by)?)?,
+            y.checked_add(dy.checked_mul(by)?)?,
+        ))
+    }
+}
+
+pub fn manhattan_distance((x1, y1): (i64, i64), (x2, y2): (i64, i64)) -> u128 {
+    u128::from(x1.abs_diff(x2)) + u128::from(y1.abs_diff(y2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turn_around() {
+        for direction in Direction::ALL {
+            assert_eq!(direction, direction.turn_left().turn_right());
+            assert_eq!(direction.opposite(), direction.turn_left().turn_left());
+            assert_eq!(direction.opposite(), direction.turn_right().turn_right());
+            assert_ne!(direction.is_vertical(), direction.turn_left().is_vertical());
+        }
+    }
+
+    #[test]
+    fn step_with_overflow_checks() {
+        assert_eq!(Some((3, 1)), Direction::Up.step((3, 2)));
+        assert_eq!(None, Direction::Up.step((3, 0)));
+        assert_eq!(None, Direction::Left.step_by((3, 0), 4));
+        assert_eq!(None, Direction::Right.step((usize::MAX, 0)));
+        assert_eq!(Some((-4, 7)), Direction::Left.step_signed_by((0, 7), 4));
+        assert_eq!(None, Direction::Down.step_signed((0, i64::MAX)));
+        assert_eq!(None, Direction::Up.step_signed_by((0, 0), i64::MIN));
+    }
+
+    #[test]
+    fn measure_manhattan_distance() {
+        assert_eq!(9, manhattan_distance((1, 6), (5, 11)));
+        assert_eq!(0, manhattan_distance((-2, 3), (-2, 3)));
+        assert_eq!(
+            2 * u128::from(u64::MAX),
+            manhattan_distance((i64::MIN, i64::MIN), (i64::MAX, i64::MAX))
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::error::Error;
+use std::io::{self, BufRead, Read};
+use std::ops::{Index, IndexMut};
+
+use common::{parse_glyph, FromGlyph, ParseError, ParseErrorKind, ParseMode};
+
+pub mod direction;
+
+pub use direction::{manhattan_distance, Direction};
+
+const NEIGHBORS8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: vec![],
+            width: 0,
+        }
+    }
+
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            cells.len().checked_rem(width).unwrap_or(cells.len()) == 0,
+            "{} cells do not fill rows of width {width}",
+            cells.len()
+        );
+
+        Self { cells, width }
+    }
+
+    pub fn parse_row_with(
+        &mut self,
+        line: &str,
+        mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+    ) -> Result<(), ParseError> {
+        let mut row = line
+            .char_indices()
+            .map(|(index, c)| parse(&line[index..index + c.len_utf8()]))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.width == 0 {
+            self.width = row.len();
+        } else if row.len() != self.width {
+            return Err(ParseError::new(
+                ParseErrorKind::WrongCount {
+                    what: "tiles",
+                    expected: self.width,
+                    found: row.len(),
+                },
+                line,
+                line,
+            ));
+        }
+
+        self.cells.append(&mut row);
+
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height()
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_signed(&self, (x, y): (i64, i64)) -> Option<&T> {
+        self.get((usize::try_from(x).ok()?, usize::try_from(y).ok()?))
+    }
+
+    pub fn get_wrapping(&self, (x, y): (i64, i64)) -> Option<&T> {
+        let width = i64::try_from(self.width).ok().filter(|&width| width > 0)?;
+        let height = i64::try_from(self.height())
+            .ok()
+            .filter(|&height| height > 0)?;
+
+        self.get((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| ((index % self.width, index / self.width), cell))
+    }
+
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(y * self.width).take(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn step(&self, pos: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        self.step_by(pos, direction, 1)
+    }
+
+    pub fn step_by(
+        &self,
+        pos: (usize, usize),
+        direction: Direction,
+        by: usize,
+    ) -> Option<(usize, usize)> {
+        direction.step_by(pos, by).filter(|&pos| self.contains(pos))
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        (x, y): (usize, usize),
+        offsets: &'a [(i64, i64)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let pos = (
+                x.checked_add_signed(dx as isize)?,
+                y.checked_add_signed(dy as isize)?,
+            );
+
+            self.contains(pos).then_some(pos)
+        })
+    }
+
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBORS8)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self::from_cells(width, vec![value; width * height])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let height = self.height();
+
+        Self::from_cells(
+            height,
+            (0..self.width)
+                .flat_map(|x| (0..height).map(move |y| self[(x, y)].clone()))
+                .collect(),
+        )
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        let height = self.height();
+
+        Self::from_cells(
+            height,
+            (0..self.width)
+                .flat_map(|x| (0..height).rev().map(move |y| self[(x, y)].clone()))
+                .collect(),
+        )
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let height = self.height();
+
+        Self::from_cells(
+            height,
+            (0..self.width)
+                .rev()
+                .flat_map(|x| (0..height).map(move |y| self[(x, y)].clone()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: FromGlyph + From<char>> Grid<T> {
+    pub fn parse_row(&mut self, line: &str, mode: ParseMode) -> Result<(), ParseError> {
+        self.parse_row_with(line, |glyph| parse_glyph(glyph, line, mode))
+    }
+
+    pub fn parse_with(input: impl Read, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut grid = Self::new();
+
+        for (index, line) in io::BufReader::new(input).lines().enumerate() {
+            grid.parse_row(&line?, mode)
+                .map_err(|e| e.at_line(index + 1))?;
+        }
+
+        Ok(grid)
+    }
+
+    pub fn parse(input: impl Read) -> Result<Self, Box<dyn Error>> {
+        Self::parse_with(input, ParseMode::Lenient)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "position {pos:?} out of bounds for {}x{} grid",
+                self.width,
+                self.height()
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height());
+
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("position {pos:?} out of bounds for {width}x{height} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Tile {
+        Open,
+        Wall,
+    }
+
+    impl FromGlyph for Tile {
+        fn from_glyph(glyph: char) -> Option<Self> {
+            match glyph {
+                '.' => Some(Tile::Open),
+                '#' => Some(Tile::Wall),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<char> for Tile {
+        fn from(value: char) -> Self {
+            Tile::from_glyph(value).unwrap_or(Tile::Open)
+        }
+    }
+
+    fn digits(input: &str) -> Grid<u32> {
+        let mut grid = Grid::new();
+
+        for line in input.lines() {
+            grid.parse_row_with(line, |glyph| common::parse_number(glyph, line))
+                .unwrap();
+        }
+
+        grid
+    }
+
+    #[test]
+    fn parse_and_access() -> Result<(), Box<dyn Error>> {
+        let grid = Grid::<Tile>::parse("#..\n.#.".as_bytes())?;
+
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&Tile::Wall), grid.get((1, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(None, grid.get_signed((-1, 0)));
+        assert_eq!(Some(&Tile::Wall), grid.get_wrapping((-3, 2)));
+        assert_eq!(
+            None,
+            Grid::<Tile>::parse("".as_bytes())?.get_wrapping((0, 0))
+        );
+        assert_eq!(Some((0, 0)), grid.position(|&tile| tile == Tile::Wall));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_ragged_rows() {
+        let error = Grid::<Tile>::parse("#..\n.#".as_bytes())
+            .err()
+            .map(|e| e.to_string());
+
+        assert_eq!(
+            Some(String::from(
+                "expected 3 tiles, found 2 `.#` at line 2, column 1
+  .#
+  ^^"
+            )),
+            error
+        );
+    }
+
+    #[test]
+    fn reject_unknown_tile_when_strict() {
+        assert!(Grid::<Tile>::parse("#.x".as_bytes()).is_ok());
+        assert!(Grid::<Tile>::parse_with("#.x".as_bytes(), ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn iterate_rows_and_columns() {
+        let grid = digits("123\n456");
+
+        assert_eq!(vec![4, 5, 6], grid.row(1).copied().collect::<Vec<_>>());
+        assert_eq!(vec![3, 6], grid.column(2).copied().collect::<Vec<_>>());
+        assert_eq!(2, grid.rows().count());
+        assert_eq!(
+            vec![5, 7, 9],
+            grid.columns()
+                .map(|column| column.sum::<u32>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn find_neighbors() {
+        let grid = digits("123\n456\n789");
+
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbors4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(4, grid.neighbors4((1, 1)).count());
+        assert_eq!(3, grid.neighbors8((2, 2)).count());
+        assert_eq!(None, grid.step((2, 0), Direction::Right));
+        assert_eq!(Some((0, 2)), grid.step_by((0, 0), Direction::Down, 2));
+        assert_eq!(8, grid.neighbors8((1, 1)).count());
+    }
+
+    #[test]
+    fn rotate_and_transpose() {
+        let grid = digits("123\n456");
+
+        assert_eq!(digits("14\n25\n36"), grid.transpose());
+        assert_eq!(digits("41\n52\n63"), grid.rotate_clockwise());
+        assert_eq!(digits("36\n25\n14"), grid.rotate_counterclockwise());
+        assert_eq!(
+            grid,
+            grid.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_counterclockwise()
+                .rotate_counterclockwise()
+        );
+    }
+}